@@ -0,0 +1,354 @@
+use crate::endpoints::latest::LatestItem;
+use crate::endpoints::prices::PricesResponseSuccess;
+use crate::ge_tax;
+use crate::types::{Coins, ItemId};
+use crate::watch::PriceSide;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod notify;
+
+use notify::{Notifier, NotifyError};
+
+/// The condition an [`AlertRule`] watches for.
+///
+/// Price and margin conditions are checked against latest prices with
+/// [`AlertEngine::evaluate_latest`], volume spikes against interval snapshots with
+/// [`AlertEngine::evaluate_prices`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    PriceBelow { side: PriceSide, price: Coins },
+    PriceAbove { side: PriceSide, price: Coins },
+    /// The price moved by at least `percent` percent, up or down, compared to the oldest price
+    /// seen within `window`.
+    PercentMove { side: PriceSide, percent: f64, window: Duration },
+    /// The instant-buy price minus the instant-sell price and GE tax exceeds `margin`.
+    MarginAbove { margin: Coins },
+    /// The traded volume of a snapshot is at least `factor` times the average of the previous
+    /// `baseline` snapshots, and at least `min_volume`.
+    VolumeSpike { factor: f64, min_volume: u64, baseline: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub name: String,
+    pub item_id: ItemId,
+    pub condition: Condition,
+    /// The minimum time between two alerts of this rule.
+    pub cooldown: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Alert {
+    pub rule: String,
+    pub item_id: ItemId,
+    pub message: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub triggered_at: DateTime<Utc>,
+}
+
+impl Display for Alert {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] item {}: {}", self.rule, self.item_id.id(), self.message)
+    }
+}
+
+impl AlertRule {
+    pub fn new(name: impl Into<String>, item_id: ItemId, condition: Condition) -> Self {
+        Self { name: name.into(), item_id, condition, cooldown: Duration::ZERO }
+    }
+
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+struct RuleState {
+    rule: AlertRule,
+    /// Whether the condition held at the last evaluation.
+    active: bool,
+    last_fired: Option<DateTime<Utc>>,
+    prices: VecDeque<(DateTime<Utc>, Coins)>,
+    volumes: VecDeque<u64>,
+}
+
+/// Evaluates [`AlertRule`]s against price data and sends the resulting alerts to notifiers.
+///
+/// A rule fires when its condition starts to hold, not on every evaluation while it keeps
+/// holding, and at most once per cooldown. It can fire again once the condition has stopped
+/// holding and then holds again.
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<RuleState>,
+    notifiers: Vec<Arc<dyn Notifier>>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: impl IntoIterator<Item=AlertRule>) -> Self {
+        let mut engine = Self::default();
+        for rule in rules {
+            engine.add_rule(rule);
+        }
+        engine
+    }
+
+    pub fn add_rule(&mut self, rule: AlertRule) {
+        self.rules.push(RuleState {
+            rule,
+            active: false,
+            last_fired: None,
+            prices: VecDeque::new(),
+            volumes: VecDeque::new(),
+        });
+    }
+
+    pub fn add_notifier(&mut self, notifier: impl Notifier + 'static) {
+        self.notifiers.push(Arc::new(notifier));
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item=&AlertRule> {
+        self.rules.iter().map(|state| &state.rule)
+    }
+
+    /// Checks price and margin rules against the latest prices.
+    pub fn evaluate_latest(&mut self, latest: &HashMap<ItemId, LatestItem>, now: DateTime<Utc>) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for state in &mut self.rules {
+            let Some(item) = latest.get(&state.rule.item_id) else {
+                continue;
+            };
+            let message = match state.rule.condition {
+                Condition::PriceBelow { side, price } => side_price(item, side).map(|current| {
+                    (current < price).then(|| format!("{} price {:#} is below {:#}", side_name(side), current, price))
+                }),
+                Condition::PriceAbove { side, price } => side_price(item, side).map(|current| {
+                    (current > price).then(|| format!("{} price {:#} is above {:#}", side_name(side), current, price))
+                }),
+                Condition::PercentMove { side, percent, window } => side_price(item, side).map(|current| {
+                    let window = chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX);
+                    state.prices.retain(|(time, _)| now.signed_duration_since(*time) <= window);
+                    state.prices.push_back((now, current));
+                    let (_, reference) = state.prices[0];
+                    let change = percent_change(reference, current)?;
+                    (change.abs() >= percent).then(|| {
+                        format!("{} price moved {:+.1}% from {:#} to {:#}", side_name(side), change, reference, current)
+                    })
+                }),
+                Condition::MarginAbove { margin } => match (item.high, item.low) {
+                    (Some(high), Some(low)) => {
+                        let tax = ge_tax::tax(state.rule.item_id, high, now);
+                        let current = high.signed_sub(low) - i64::from(tax);
+                        let current = Coins::new(u32::try_from(current.max(0)).unwrap_or(u32::MAX));
+                        Some((current > margin).then(|| format!("margin after tax {:#} is above {:#}", current, margin)))
+                    }
+                    _ => None,
+                },
+                Condition::VolumeSpike { .. } => None,
+            };
+            if let Some(message) = message {
+                alerts.extend(state.update(message, now));
+            }
+        }
+        alerts
+    }
+
+    /// Checks volume rules against an interval snapshot, such as the 5-minute or 1-hour prices.
+    pub fn evaluate_prices(&mut self, prices: &PricesResponseSuccess) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for state in &mut self.rules {
+            let Condition::VolumeSpike { factor, min_volume, baseline } = state.rule.condition else {
+                continue;
+            };
+            let volume = prices
+                .data
+                .get(&state.rule.item_id)
+                .map_or(0, |item| u64::from(item.high_price_volume) + u64::from(item.low_price_volume));
+            let message = if state.volumes.is_empty() {
+                None
+            } else {
+                let average = state.volumes.iter().sum::<u64>() as f64 / state.volumes.len() as f64;
+                (volume >= min_volume && volume as f64 >= factor * average)
+                    .then(|| format!("volume {} is {:.1}x the average of {:.0}", volume, volume as f64 / average, average))
+            };
+            state.volumes.push_back(volume);
+            while state.volumes.len() > baseline.max(1) {
+                state.volumes.pop_front();
+            }
+            alerts.extend(state.update(message, prices.timestamp));
+        }
+        alerts
+    }
+
+    /// Sends every alert to every notifier.
+    ///
+    /// A failing notifier doesn't stop the others; the first error is returned once all alerts
+    /// have been sent.
+    pub async fn notify(&self, alerts: &[Alert]) -> Result<(), NotifyError> {
+        let mut result = Ok(());
+        for alert in alerts {
+            for notifier in &self.notifiers {
+                if let Err(error) = notifier.notify(alert).await
+                    && result.is_ok()
+                {
+                    result = Err(error);
+                }
+            }
+        }
+        result
+    }
+}
+
+impl RuleState {
+    fn update(&mut self, message: Option<String>, now: DateTime<Utc>) -> Option<Alert> {
+        let Some(message) = message else {
+            self.active = false;
+            return None;
+        };
+        if std::mem::replace(&mut self.active, true) {
+            return None;
+        }
+        let cooldown = chrono::Duration::from_std(self.rule.cooldown).unwrap_or(chrono::Duration::MAX);
+        if self.last_fired.is_some_and(|last_fired| now.signed_duration_since(last_fired) < cooldown) {
+            return None;
+        }
+        self.last_fired = Some(now);
+        Some(Alert { rule: self.rule.name.clone(), item_id: self.rule.item_id, message, triggered_at: now })
+    }
+}
+
+fn side_price(item: &LatestItem, side: PriceSide) -> Option<Coins> {
+    match side {
+        PriceSide::High => item.high,
+        PriceSide::Low => item.low,
+    }
+}
+
+fn side_name(side: PriceSide) -> &'static str {
+    match side {
+        PriceSide::High => "high",
+        PriceSide::Low => "low",
+    }
+}
+
+fn percent_change(from: Coins, to: Coins) -> Option<f64> {
+    (from > Coins::ZERO).then(|| to.signed_sub(from) as f64 / f64::from(from.amount()) * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alerts::notify::FileNotifier;
+    use crate::alerts::{Alert, AlertEngine, AlertRule, Condition};
+    use crate::endpoints::latest::LatestItem;
+    use crate::endpoints::prices::{PriceItem, PricesResponseSuccess};
+    use crate::types::{Coins, ItemId};
+    use crate::watch::PriceSide;
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    const WHIP: ItemId = ItemId::new(4151);
+
+    fn start() -> DateTime<Utc> {
+        Utc.timestamp_opt(1760702400, 0).unwrap()
+    }
+
+    fn latest(high: u32, low: u32) -> HashMap<ItemId, LatestItem> {
+        let item = LatestItem { high: Some(Coins::new(high)), low: Some(Coins::new(low)), high_time: Some(start()), low_time: Some(start()) };
+        HashMap::from([(WHIP, item)])
+    }
+
+    fn prices(volume: u32, minutes: i64) -> PricesResponseSuccess {
+        let item = PriceItem { avg_high_price: None, avg_low_price: None, high_price_volume: volume, low_price_volume: 0 };
+        PricesResponseSuccess { data: HashMap::from([(WHIP, item)]), timestamp: start() + TimeDelta::minutes(minutes) }
+    }
+
+    #[test]
+    fn test_threshold_deduplication_and_cooldown() {
+        let rule = AlertRule::new("cheap whip", WHIP, Condition::PriceBelow { side: PriceSide::Low, price: Coins::new(1_500_000) })
+            .cooldown(Duration::from_secs(600));
+        let mut engine = AlertEngine::new([rule]);
+        let at = |minutes| start() + TimeDelta::minutes(minutes);
+
+        assert!(engine.evaluate_latest(&latest(1_520_000, 1_510_000), at(0)).is_empty());
+        let alerts = engine.evaluate_latest(&latest(1_500_000, 1_490_000), at(1));
+        assert_eq!(
+            alerts,
+            vec![Alert {
+                rule: "cheap whip".to_string(),
+                item_id: WHIP,
+                message: "low price 1,490,000 is below 1,500,000".to_string(),
+                triggered_at: at(1),
+            }]
+        );
+        // Still below: no duplicate alert.
+        assert!(engine.evaluate_latest(&latest(1_500_000, 1_480_000), at(2)).is_empty());
+        // Recovered and dropped again within the cooldown.
+        assert!(engine.evaluate_latest(&latest(1_520_000, 1_510_000), at(3)).is_empty());
+        assert!(engine.evaluate_latest(&latest(1_500_000, 1_490_000), at(4)).is_empty());
+        // Recovered and dropped again after the cooldown.
+        assert!(engine.evaluate_latest(&latest(1_520_000, 1_510_000), at(12)).is_empty());
+        assert_eq!(engine.evaluate_latest(&latest(1_500_000, 1_490_000), at(13)).len(), 1);
+    }
+
+    #[test]
+    fn test_percent_move() {
+        let condition = Condition::PercentMove { side: PriceSide::High, percent: 5.0, window: Duration::from_secs(600) };
+        let mut engine = AlertEngine::new([AlertRule::new("whip move", WHIP, condition)]);
+        let at = |minutes| start() + TimeDelta::minutes(minutes);
+
+        assert!(engine.evaluate_latest(&latest(1_500_000, 1_490_000), at(0)).is_empty());
+        assert!(engine.evaluate_latest(&latest(1_540_000, 1_490_000), at(5)).is_empty());
+        let alerts = engine.evaluate_latest(&latest(1_580_000, 1_490_000), at(9));
+        assert_eq!(alerts[0].message, "high price moved +5.3% from 1,500,000 to 1,580,000");
+        // The first price has left the window, so the move is measured from 1,540,000.
+        assert!(engine.evaluate_latest(&latest(1_580_000, 1_490_000), at(12)).is_empty());
+        let alerts = engine.evaluate_latest(&latest(1_460_000, 1_490_000), at(14));
+        assert_eq!(alerts[0].message, "high price moved -5.2% from 1,540,000 to 1,460,000");
+    }
+
+    #[test]
+    fn test_margin() {
+        let rule = AlertRule::new("whip margin", WHIP, Condition::MarginAbove { margin: Coins::new(10_000) });
+        let mut engine = AlertEngine::new([rule]);
+        // 1,520,000 - 1,490,000 - 30,400 tax
+        assert!(engine.evaluate_latest(&latest(1_520_000, 1_490_000), start()).is_empty());
+        let alerts = engine.evaluate_latest(&latest(1_540_000, 1_490_000), start());
+        assert_eq!(alerts[0].message, "margin after tax 19,200 is above 10,000");
+    }
+
+    #[test]
+    fn test_volume_spike() {
+        let condition = Condition::VolumeSpike { factor: 3.0, min_volume: 100, baseline: 3 };
+        let mut engine = AlertEngine::new([AlertRule::new("whip volume", WHIP, condition)]);
+        for (minutes, volume) in [(0, 40), (5, 60), (10, 50)] {
+            assert!(engine.evaluate_prices(&prices(volume, minutes)).is_empty());
+        }
+        let alerts = engine.evaluate_prices(&prices(160, 15));
+        assert_eq!(alerts[0].message, "volume 160 is 3.2x the average of 50");
+        assert_eq!(alerts[0].triggered_at, start() + TimeDelta::minutes(15));
+        // The spike is now part of the baseline.
+        assert!(engine.evaluate_prices(&prices(200, 20)).is_empty());
+        // Latest prices don't affect volume rules.
+        assert!(engine.evaluate_latest(&latest(1, 1), start()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_notify() {
+        let path = std::env::temp_dir().join(format!("osrs-wiki-prices-alerts-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let rule = AlertRule::new("cheap whip", WHIP, Condition::PriceBelow { side: PriceSide::Low, price: Coins::new(1_500_000) });
+        let mut engine = AlertEngine::new([rule]);
+        engine.add_notifier(FileNotifier::new(&path));
+
+        let alerts = engine.evaluate_latest(&latest(1_500_000, 1_490_000), start());
+        engine.notify(&alerts).await.unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let written: Alert = serde_json::from_str(contents.trim_end()).unwrap();
+        assert_eq!(written, alerts[0]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
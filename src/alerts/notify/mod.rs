@@ -0,0 +1,178 @@
+use crate::alerts::Alert;
+use futures_channel::oneshot;
+use reqwest::StatusCode;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+pub type NotifyFuture<'a> = Pin<Box<dyn Future<Output=Result<(), NotifyError>> + Send + 'a>>;
+
+/// Delivers alerts somewhere, such as a terminal, a file or a chat webhook.
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> NotifyFuture<'a>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NotifyError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("Webhook responded with status {0}")]
+    Status(StatusCode),
+}
+
+/// Prints each alert as a line on standard output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> NotifyFuture<'a> {
+        println!("{}", alert);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+/// Appends each alert to a file as a line of JSON.
+///
+/// Writes run on a separate thread, so notifying never blocks the executor.
+#[derive(Debug)]
+pub struct FileNotifier {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl FileNotifier {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), lock: Arc::default() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+fn append_line(path: &Path, lock: &Mutex<()>, line: &[u8]) -> Result<(), NotifyError> {
+    let _guard = lock.lock().unwrap();
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line)?;
+    Ok(())
+}
+
+impl Notifier for FileNotifier {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> NotifyFuture<'a> {
+        let mut line = match serde_json::to_vec(alert) {
+            Ok(line) => line,
+            Err(error) => return Box::pin(std::future::ready(Err(error.into()))),
+        };
+        line.push(b'\n');
+        let (path, lock) = (self.path.clone(), self.lock.clone());
+        let (sender, receiver) = oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(append_line(&path, &lock, &line));
+        });
+        Box::pin(async move {
+            receiver
+                .await
+                .unwrap_or_else(|_| Err(std::io::Error::other("File notifier thread panicked").into()))
+        })
+    }
+}
+
+/// Posts each alert as JSON to a URL.
+///
+/// The body is the serialized [`Alert`]. Any 2xx response counts as delivered.
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    http_client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Result<Self, NotifyError> {
+        Ok(Self::with_client(reqwest::Client::builder().build()?, url))
+    }
+
+    pub fn with_client(http_client: reqwest::Client, url: impl Into<String>) -> Self {
+        Self { http_client, url: url.into() }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify<'a>(&'a self, alert: &'a Alert) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let response = self.http_client.post(&self.url).json(alert).send().await?;
+            if !response.status().is_success() {
+                return Err(NotifyError::Status(response.status()));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alerts::notify::{FileNotifier, Notifier, NotifyError, WebhookNotifier};
+    use crate::alerts::Alert;
+    use crate::testing::{http_response, start_mock_server};
+    use crate::types::ItemId;
+    use chrono::{TimeZone, Utc};
+    use reqwest::StatusCode;
+
+    fn alert(message: &str) -> Alert {
+        Alert {
+            rule: "cheap whip".to_string(),
+            item_id: ItemId::new(4151),
+            message: message.to_string(),
+            triggered_at: Utc.timestamp_opt(1760702400, 0).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_notifier() {
+        let path = std::env::temp_dir().join(format!("osrs-wiki-prices-notify-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let notifier = FileNotifier::new(&path);
+        notifier.notify(&alert("first")).await.unwrap();
+        notifier.notify(&alert("second")).await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"rule":"cheap whip","item_id":4151,"message":"first","triggered_at":1760702400}"#,
+                r#"{"rule":"cheap whip","item_id":4151,"message":"second","triggered_at":1760702400}"#,
+            ]
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier() {
+        let server = start_mock_server(vec![
+            http_response(204, &[], ""),
+            http_response(500, &[], "broken"),
+        ])
+        .await;
+        let notifier = WebhookNotifier::new(format!("{}/hooks/alerts", server.base_url)).unwrap();
+        notifier.notify(&alert("low price 1,490,000 is below 1,500,000")).await.unwrap();
+        let err = notifier.notify(&alert("again")).await.unwrap_err();
+        assert!(matches!(err, NotifyError::Status(StatusCode::INTERNAL_SERVER_ERROR)));
+
+        let requests = server.requests.lock().unwrap();
+        let request = &requests[0];
+        assert!(request.starts_with("POST /hooks/alerts HTTP/1.1\r\n"));
+        assert!(request.to_ascii_lowercase().contains("content-type: application/json"));
+        let (_, body) = request.split_once("\r\n\r\n").unwrap();
+        let body: Alert = serde_json::from_str(body).unwrap();
+        assert_eq!(body, alert("low price 1,490,000 is below 1,500,000"));
+    }
+}
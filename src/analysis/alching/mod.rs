@@ -0,0 +1,176 @@
+use crate::endpoints::latest::LatestItem;
+use crate::endpoints::mapping::MappingItem;
+use crate::types::{Coins, ItemId};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+pub const NATURE_RUNE: ItemId = ItemId::new(561);
+pub const FIRE_RUNE: ItemId = ItemId::new(554);
+/// Fire runes used by one cast of High Level Alchemy.
+pub const FIRE_RUNES_PER_CAST: u32 = 5;
+
+/// Assumptions about how the alchemy spells are cast.
+#[derive(Debug, Clone, Default)]
+pub struct AlchSetup {
+    /// Whether a fire staff (or another source of unlimited fire runes) is equipped.
+    pub fire_staff: bool,
+    /// Casts that cost no runes, e.g. the daily charges of an Explorer's ring.
+    pub free_casts: u32,
+    pub members: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlchResult {
+    pub item_id: ItemId,
+    pub name: String,
+    pub members: bool,
+    pub high_alch: Coins,
+    /// The latest instant-buy price of the item.
+    pub buy_price: Coins,
+    pub nature_rune_price: Coins,
+    /// The price of a fire rune, or `None` when a fire staff is used.
+    pub fire_rune_price: Option<Coins>,
+    /// Cost of the runes for one cast.
+    pub rune_cost: Coins,
+    /// Profit of one cast paid for with runes.
+    pub profit: i64,
+    pub limit: Option<u32>,
+    /// Profit of alching a full buy limit, with [`AlchSetup::free_casts`] saving their rune cost.
+    pub limit_profit: Option<i64>,
+}
+
+impl AlchResult {
+    fn rank(&self) -> i64 {
+        self.limit_profit.unwrap_or(self.profit)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AlchError {
+    #[error("No buy price for rune {0:?} in the latest prices")]
+    MissingRunePrice(ItemId),
+}
+
+/// Ranks items by the profit of buying them at the latest instant-buy price and casting High
+/// Level Alchemy on them, with rune prices taken from the same snapshot.
+pub fn high_alch_profits(
+    latest: &HashMap<ItemId, LatestItem>,
+    mapping: &[MappingItem],
+    setup: &AlchSetup,
+) -> Result<Vec<AlchResult>, AlchError> {
+    let rune_price = |rune: ItemId| latest.get(&rune).and_then(|item| item.high).ok_or(AlchError::MissingRunePrice(rune));
+    let nature_rune_price = rune_price(NATURE_RUNE)?;
+    let fire_rune_price = if setup.fire_staff { None } else { Some(rune_price(FIRE_RUNE)?) };
+    let fire_rune_cost = fire_rune_price.map_or(0, |price| price.widening_mul(FIRE_RUNES_PER_CAST));
+    let rune_cost = Coins::new(u32::try_from(u64::from(nature_rune_price) + fire_rune_cost).unwrap_or(u32::MAX));
+
+    let mut results: Vec<AlchResult> = mapping
+        .iter()
+        .filter(|item| setup.members.is_none_or(|members| item.members == members))
+        .filter_map(|item| {
+            let high_alch = item.high_alch?;
+            let buy_price = latest.get(&item.id)?.high?;
+            let profit = high_alch.signed_sub(buy_price) - i64::from(rune_cost);
+            let limit_profit = item.limit.map(|limit| {
+                let free_casts = setup.free_casts.min(limit);
+                profit * i64::from(limit) + i64::from(rune_cost) * i64::from(free_casts)
+            });
+            Some(AlchResult {
+                item_id: item.id,
+                name: item.name.clone(),
+                members: item.members,
+                high_alch,
+                buy_price,
+                nature_rune_price,
+                fire_rune_price,
+                rune_cost,
+                profit,
+                limit: item.limit,
+                limit_profit,
+            })
+        })
+        .collect();
+    results.sort_by_key(|result| Reverse(result.rank()));
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::alching::{high_alch_profits, AlchError, AlchSetup, FIRE_RUNE, NATURE_RUNE};
+    use crate::endpoints::latest::LatestItem;
+    use crate::endpoints::mapping::MappingItem;
+    use crate::types::{Coins, ItemId};
+    use std::collections::HashMap;
+
+    fn mapping_item(id: u32, name: &str, members: bool, high_alch: u32, limit: u32) -> MappingItem {
+        MappingItem {
+            id: ItemId::new(id),
+            name: name.to_string(),
+            examine: String::new(),
+            members,
+            low_alch: None,
+            high_alch: Some(Coins::new(high_alch)),
+            value: None,
+            limit: Some(limit),
+            icon: format!("{}.png", name),
+        }
+    }
+
+    fn latest_item(high: Option<u32>, low: u32) -> LatestItem {
+        LatestItem { high: high.map(Coins::new), low: Some(Coins::new(low)), high_time: None, low_time: None }
+    }
+
+    fn market() -> (HashMap<ItemId, LatestItem>, Vec<MappingItem>) {
+        let latest = HashMap::from([
+            (FIRE_RUNE, latest_item(Some(5), 4)),
+            (NATURE_RUNE, latest_item(Some(105), 103)),
+            (ItemId::new(1755), latest_item(None, 2)),
+            (ItemId::new(4151), latest_item(Some(1_502_000), 1_490_123)),
+            (ItemId::new(11832), latest_item(Some(14_880_000), 14_702_001)),
+        ]);
+        let mapping = vec![
+            mapping_item(554, "Fire rune", false, 2, 50_000),
+            mapping_item(561, "Nature rune", false, 108, 18_000),
+            mapping_item(1755, "Chisel", false, 0, 40),
+            mapping_item(4151, "Abyssal whip", true, 72_000, 70),
+            mapping_item(11832, "Bandos chestplate", true, 159_011, 8),
+        ];
+        (latest, mapping)
+    }
+
+    #[test]
+    fn test_high_alch_profits() {
+        let (mut latest, mapping) = market();
+        latest.insert(ItemId::new(4151), LatestItem { high: Some(Coins::new(71_000)), ..latest[&ItemId::new(4151)].clone() });
+        let results = high_alch_profits(&latest, &mapping, &AlchSetup::default()).unwrap();
+        let whip = &results[0];
+        assert_eq!(whip.item_id, ItemId::new(4151));
+        assert_eq!(whip.nature_rune_price, Coins::new(105));
+        assert_eq!(whip.fire_rune_price, Some(Coins::new(5)));
+        assert_eq!(whip.rune_cost, Coins::new(130));
+        assert_eq!(whip.profit, 72_000 - 71_000 - 130);
+        assert_eq!(whip.limit_profit, Some(870 * 70));
+        assert!(results.windows(2).all(|pair| pair[0].limit_profit >= pair[1].limit_profit));
+        assert!(results.iter().all(|result| result.item_id != ItemId::new(1755)));
+    }
+
+    #[test]
+    fn test_high_alch_setup() {
+        let (latest, mapping) = market();
+
+        let setup = AlchSetup { fire_staff: true, free_casts: 30, members: Some(true) };
+        let results = high_alch_profits(&latest, &mapping, &setup).unwrap();
+        assert!(results.iter().all(|result| result.members && result.fire_rune_price.is_none()));
+        let whip = results.iter().find(|result| result.item_id == ItemId::new(4151)).unwrap();
+        assert_eq!(whip.rune_cost, Coins::new(105));
+        assert_eq!(whip.profit, 72_000 - 1_502_000 - 105);
+        assert_eq!(whip.limit_profit, Some(whip.profit * 70 + 105 * 30));
+
+        let mut latest = latest;
+        latest.remove(&FIRE_RUNE);
+        assert!(high_alch_profits(&latest, &mapping, &setup).is_ok());
+        assert_eq!(high_alch_profits(&latest, &mapping, &AlchSetup::default()), Err(AlchError::MissingRunePrice(FIRE_RUNE)));
+        latest.remove(&NATURE_RUNE);
+        assert_eq!(high_alch_profits(&latest, &mapping, &setup), Err(AlchError::MissingRunePrice(NATURE_RUNE)));
+    }
+}
@@ -0,0 +1,234 @@
+use crate::endpoints::latest::LatestItem;
+use crate::endpoints::mapping::MappingItem;
+use crate::endpoints::prices::PricesResponseSuccess;
+use crate::ge_tax;
+use crate::types::{Coins, ItemId};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// Filters applied by [`scan_flips`]. Unset fields do not filter.
+#[derive(Debug, Clone, Default)]
+pub struct FlipFilter {
+    pub members: Option<bool>,
+    /// Minimum number of items traded in the hourly prices, counting both sides.
+    pub min_volume: Option<u64>,
+    /// Maximum age of the older of the latest buy and sell trades.
+    pub max_price_age: Option<Duration>,
+    /// Minimum post-tax return on investment, as a fraction (`0.01` is 1%).
+    pub min_roi: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlipOpportunity {
+    pub item_id: ItemId,
+    pub name: String,
+    pub members: bool,
+    /// The latest instant-sell price, at which offers can be bought.
+    pub buy_price: Coins,
+    /// The latest instant-buy price, at which offers can be sold.
+    pub sell_price: Coins,
+    pub margin: i64,
+    pub tax: Coins,
+    pub post_tax_margin: i64,
+    pub roi: f64,
+    pub limit: Option<u32>,
+    /// Post-tax margin over a full buy limit.
+    pub potential_profit: Option<i64>,
+    pub volume: Option<u64>,
+    pub high_time: DateTime<Utc>,
+    pub low_time: DateTime<Utc>,
+    /// Age of the older of the two trades the margin is based on.
+    pub price_age: Duration,
+}
+
+impl FlipOpportunity {
+    fn rank(&self) -> i64 {
+        self.potential_profit.unwrap_or(self.post_tax_margin)
+    }
+}
+
+/// Joins latest prices with the item mapping and returns items with a positive post-tax margin,
+/// ranked by potential profit over a buy limit (or by margin for items without a limit).
+///
+/// `hourly` provides trade volumes for [`FlipFilter::min_volume`]; without it, volumes are unknown
+/// and a volume filter excludes every item.
+pub fn scan_flips(
+    latest: &HashMap<ItemId, LatestItem>,
+    mapping: &[MappingItem],
+    hourly: Option<&PricesResponseSuccess>,
+    filter: &FlipFilter,
+    now: DateTime<Utc>,
+) -> Vec<FlipOpportunity> {
+    let mut opportunities: Vec<FlipOpportunity> = mapping
+        .iter()
+        .filter(|item| filter.members.is_none_or(|members| item.members == members))
+        .filter_map(|item| {
+            let prices = latest.get(&item.id)?;
+            let (buy_price, low_time) = (prices.low?, prices.low_time?);
+            let (sell_price, high_time) = (prices.high?, prices.high_time?);
+            let price_age = now - high_time.min(low_time);
+            if filter.max_price_age.is_some_and(|max_age| price_age > max_age) {
+                return None;
+            }
+            let volume = hourly
+                .and_then(|hourly| hourly.data.get(&item.id))
+                .map(|price| u64::from(price.high_price_volume) + u64::from(price.low_price_volume));
+            if let Some(min_volume) = filter.min_volume
+                && volume.is_none_or(|volume| volume < min_volume)
+            {
+                return None;
+            }
+            let tax = ge_tax::tax(item.id, sell_price, now);
+            let margin = sell_price.signed_sub(buy_price);
+            let post_tax_margin = margin - i64::from(tax);
+            if post_tax_margin <= 0 || buy_price == Coins::ZERO {
+                return None;
+            }
+            let roi = post_tax_margin as f64 / f64::from(buy_price.amount());
+            if filter.min_roi.is_some_and(|min_roi| roi < min_roi) {
+                return None;
+            }
+            Some(FlipOpportunity {
+                item_id: item.id,
+                name: item.name.clone(),
+                members: item.members,
+                buy_price,
+                sell_price,
+                margin,
+                tax,
+                post_tax_margin,
+                roi,
+                limit: item.limit,
+                potential_profit: item.limit.map(|limit| post_tax_margin * i64::from(limit)),
+                volume,
+                high_time,
+                low_time,
+                price_age,
+            })
+        })
+        .collect();
+    opportunities.sort_by(|a, b| b.rank().cmp(&a.rank()).then(b.roi.total_cmp(&a.roi)));
+    opportunities
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::flips::{scan_flips, FlipFilter};
+    use crate::endpoints::latest::LatestItem;
+    use crate::endpoints::mapping::MappingItem;
+    use crate::endpoints::prices::{PriceItem, PricesResponseSuccess};
+    use crate::types::{Coins, ItemId};
+    use chrono::{DateTime, Duration, Utc};
+    use std::collections::HashMap;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1760702400, 0).unwrap()
+    }
+
+    fn latest_item(low: u32, high: u32, age_seconds: i64) -> LatestItem {
+        LatestItem {
+            high: Some(Coins::new(high)),
+            low: Some(Coins::new(low)),
+            high_time: Some(now() - Duration::seconds(age_seconds)),
+            low_time: Some(now() - Duration::seconds(age_seconds / 2)),
+        }
+    }
+
+    fn mapping_item(id: u32, name: &str, members: bool, limit: u32) -> MappingItem {
+        MappingItem {
+            id: ItemId::new(id),
+            name: name.to_string(),
+            examine: String::new(),
+            members,
+            low_alch: None,
+            high_alch: None,
+            value: None,
+            limit: Some(limit),
+            icon: format!("{}.png", name),
+        }
+    }
+
+    fn price_item(high_price_volume: u32, low_price_volume: u32) -> PriceItem {
+        PriceItem { avg_high_price: None, high_price_volume, avg_low_price: None, low_price_volume }
+    }
+
+    fn fixtures() -> (HashMap<ItemId, LatestItem>, Vec<MappingItem>, PricesResponseSuccess) {
+        let latest = HashMap::from([
+            (ItemId::new(554), latest_item(4, 5, 30)),
+            (ItemId::new(1755), latest_item(2, 10, 60)),
+            (ItemId::new(4151), latest_item(1_450_000, 1_520_000, 120)),
+            (ItemId::new(11832), latest_item(14_500_000, 15_100_000, 3_600)),
+            (ItemId::new(12934), latest_item(149, 151, 30)),
+        ]);
+        let mapping = vec![
+            mapping_item(554, "Fire rune", false, 50_000),
+            mapping_item(1755, "Chisel", false, 40),
+            mapping_item(4151, "Abyssal whip", true, 70),
+            mapping_item(11832, "Bandos chestplate", true, 8),
+            mapping_item(12934, "Zulrah's scales", true, 30_000),
+        ];
+        let hourly = PricesResponseSuccess {
+            data: HashMap::from([
+                (ItemId::new(554), price_item(2_502_133, 1_988_002)),
+                (ItemId::new(1755), price_item(0, 40)),
+                (ItemId::new(4151), price_item(112, 98)),
+                (ItemId::new(11832), price_item(6, 4)),
+                (ItemId::new(12934), price_item(2_108_843, 1_902_211)),
+            ]),
+            timestamp: DateTime::from_timestamp(1760698800, 0).unwrap(),
+        };
+        (latest, mapping, hourly)
+    }
+
+    #[test]
+    fn test_scan_flips() {
+        let (latest, mapping, hourly) = fixtures();
+        let opportunities = scan_flips(&latest, &mapping, Some(&hourly), &FlipFilter::default(), now());
+        let ids: Vec<u32> = opportunities.iter().map(|opportunity| opportunity.item_id.id()).collect();
+        assert_eq!(ids, vec![4151, 11832, 554, 1755]);
+
+        let whip = &opportunities[0];
+        assert_eq!(whip.name, "Abyssal whip");
+        assert_eq!(whip.buy_price, Coins::new(1_450_000));
+        assert_eq!(whip.sell_price, Coins::new(1_520_000));
+        assert_eq!(whip.margin, 70_000);
+        assert_eq!(whip.tax, Coins::new(30_400));
+        assert_eq!(whip.post_tax_margin, 39_600);
+        assert_eq!(whip.potential_profit, Some(39_600 * 70));
+        assert_eq!(whip.price_age, Duration::seconds(120));
+        assert!((whip.roi - 39_600.0 / 1_450_000.0).abs() < f64::EPSILON);
+
+        let bandos = &opportunities[1];
+        assert_eq!(bandos.tax, Coins::new(302_000));
+        assert_eq!(bandos.post_tax_margin, 600_000 - 302_000);
+
+        let chisel = &opportunities[3];
+        assert_eq!(chisel.tax, Coins::ZERO);
+        assert_eq!(chisel.post_tax_margin, 8);
+    }
+
+    #[test]
+    fn test_scan_flips_filters() {
+        let (latest, mapping, hourly) = fixtures();
+
+        let filter = FlipFilter { members: Some(true), ..FlipFilter::default() };
+        let opportunities = scan_flips(&latest, &mapping, Some(&hourly), &filter, now());
+        assert!(opportunities.iter().all(|opportunity| opportunity.members));
+        assert_eq!(opportunities.len(), 2);
+
+        let filter = FlipFilter { min_volume: Some(100), ..FlipFilter::default() };
+        let opportunities = scan_flips(&latest, &mapping, Some(&hourly), &filter, now());
+        assert!(opportunities.iter().all(|opportunity| opportunity.volume >= Some(100)));
+        assert_eq!(opportunities.len(), 2);
+        assert!(scan_flips(&latest, &mapping, None, &filter, now()).is_empty());
+
+        let filter = FlipFilter { min_roi: Some(0.1), ..FlipFilter::default() };
+        let opportunities = scan_flips(&latest, &mapping, Some(&hourly), &filter, now());
+        assert!(opportunities.iter().all(|opportunity| opportunity.roi >= 0.1));
+        assert_eq!(opportunities.len(), 2);
+
+        let filter = FlipFilter { max_price_age: Some(Duration::minutes(5)), ..FlipFilter::default() };
+        let opportunities = scan_flips(&latest, &mapping, Some(&hourly), &filter, now());
+        assert!(opportunities.iter().all(|opportunity| opportunity.item_id != ItemId::new(11832)));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod alching;
+pub mod flips;
@@ -0,0 +1,463 @@
+use crate::endpoints::latest::LatestItem;
+use crate::endpoints::mapping::MappingItem;
+use crate::endpoints::prices::{PriceItem, PricesResponseSuccess};
+use crate::endpoints::timeseries::{TimeseriesItem, Timestep};
+use crate::types::{Coins, ItemId};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+/// Schema migrations, applied in order. The database's `user_version` is the number of
+/// migrations it has.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE items (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        examine TEXT NOT NULL,
+        members INTEGER NOT NULL,
+        low_alch INTEGER,
+        high_alch INTEGER,
+        value INTEGER,
+        buy_limit INTEGER,
+        icon TEXT NOT NULL
+    );
+    CREATE TABLE trades (
+        item_id INTEGER NOT NULL,
+        side TEXT NOT NULL CHECK (side IN ('high', 'low')),
+        time INTEGER NOT NULL,
+        price INTEGER NOT NULL,
+        PRIMARY KEY (item_id, side, time)
+    ) WITHOUT ROWID;
+    CREATE TABLE prices (
+        item_id INTEGER NOT NULL,
+        interval TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        avg_high_price INTEGER,
+        high_price_volume INTEGER NOT NULL,
+        avg_low_price INTEGER,
+        low_price_volume INTEGER NOT NULL,
+        PRIMARY KEY (item_id, interval, timestamp)
+    ) WITHOUT ROWID;
+    CREATE INDEX prices_by_interval ON prices (interval, timestamp);",
+];
+
+const INTERVALS: [Timestep; 4] = [Timestep::FiveMinutes, Timestep::OneHour, Timestep::SixHours, Timestep::OneDay];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Database schema version {found} is newer than the latest supported version {supported}")]
+    UnsupportedVersion { found: usize, supported: usize },
+}
+
+/// A SQLite database of item mappings, latest trades and interval prices.
+///
+/// Every insert is an upsert: trades are keyed by item, side and time, and interval prices by
+/// item, interval and timestamp, so ingesting overlapping data twice doesn't duplicate it. Interval
+/// snapshots and timeseries share the same table, since a timeseries point is the snapshot of one
+/// item.
+///
+/// The archive is synchronous; from async code, run it on a blocking thread.
+#[derive(Debug)]
+pub struct PriceArchive {
+    connection: Connection,
+}
+
+impl PriceArchive {
+    /// Opens or creates the archive at `path`, migrating it to the latest schema.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArchiveError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, ArchiveError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    pub fn from_connection(mut connection: Connection) -> Result<Self, ArchiveError> {
+        migrate(&mut connection)?;
+        Ok(Self { connection })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Returns the schema version, the number of applied migrations.
+    pub fn schema_version(&self) -> Result<usize, ArchiveError> {
+        schema_version(&self.connection)
+    }
+
+    /// Stores item mappings, replacing earlier versions of the same items.
+    pub fn insert_mapping(&mut self, items: &[MappingItem]) -> Result<(), ArchiveError> {
+        let transaction = self.connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT INTO items (id, name, examine, members, low_alch, high_alch, value, buy_limit, icon)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT (id) DO UPDATE SET
+                    name = excluded.name, examine = excluded.examine, members = excluded.members,
+                    low_alch = excluded.low_alch, high_alch = excluded.high_alch, value = excluded.value,
+                    buy_limit = excluded.buy_limit, icon = excluded.icon",
+            )?;
+            for item in items {
+                statement.execute(params![
+                    item.id.id(),
+                    item.name,
+                    item.examine,
+                    item.members,
+                    item.low_alch.map(|coins| coins.amount()),
+                    item.high_alch.map(|coins| coins.amount()),
+                    item.value.map(|coins| coins.amount()),
+                    item.limit,
+                    item.icon,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Stores the trades of a latest prices response.
+    pub fn insert_latest(&mut self, latest: &HashMap<ItemId, LatestItem>) -> Result<(), ArchiveError> {
+        let transaction = self.connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT INTO trades (item_id, side, time, price) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (item_id, side, time) DO UPDATE SET price = excluded.price",
+            )?;
+            for (item_id, item) in latest {
+                for (side, price, time) in [("high", item.high, item.high_time), ("low", item.low, item.low_time)] {
+                    if let (Some(price), Some(time)) = (price, time) {
+                        statement.execute(params![item_id.id(), side, time.timestamp(), price.amount()])?;
+                    }
+                }
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Stores an interval snapshot of all items.
+    pub fn insert_prices(&mut self, interval: Timestep, snapshot: &PricesResponseSuccess) -> Result<(), ArchiveError> {
+        let rows = snapshot.data.iter().map(|(item_id, item)| {
+            let row = (item.avg_high_price, item.high_price_volume, item.avg_low_price, item.low_price_volume);
+            (*item_id, snapshot.timestamp, row)
+        });
+        self.upsert_prices(interval, rows)
+    }
+
+    /// Stores the timeseries of one item.
+    pub fn insert_timeseries(&mut self, item_id: ItemId, timestep: Timestep, timeseries: &[TimeseriesItem]) -> Result<(), ArchiveError> {
+        let rows = timeseries.iter().map(|item| {
+            let row = (item.avg_high_price, item.high_price_volume, item.avg_low_price, item.low_price_volume);
+            (item_id, item.timestamp, row)
+        });
+        self.upsert_prices(timestep, rows)
+    }
+
+    fn upsert_prices(
+        &mut self,
+        interval: Timestep,
+        rows: impl Iterator<Item=(ItemId, DateTime<Utc>, (Option<Coins>, u32, Option<Coins>, u32))>,
+    ) -> Result<(), ArchiveError> {
+        let transaction = self.connection.transaction()?;
+        {
+            let mut statement = transaction.prepare_cached(
+                "INSERT INTO prices (item_id, interval, timestamp, avg_high_price, high_price_volume, avg_low_price, low_price_volume)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (item_id, interval, timestamp) DO UPDATE SET
+                    avg_high_price = excluded.avg_high_price, high_price_volume = excluded.high_price_volume,
+                    avg_low_price = excluded.avg_low_price, low_price_volume = excluded.low_price_volume",
+            )?;
+            for (item_id, timestamp, (avg_high_price, high_price_volume, avg_low_price, low_price_volume)) in rows {
+                statement.execute(params![
+                    item_id.id(),
+                    interval.as_ref(),
+                    timestamp.timestamp(),
+                    avg_high_price.map(|coins| coins.amount()),
+                    high_price_volume,
+                    avg_low_price.map(|coins| coins.amount()),
+                    low_price_volume,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    pub fn item(&self, id: ItemId) -> Result<Option<MappingItem>, ArchiveError> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT id, name, examine, members, low_alch, high_alch, value, buy_limit, icon FROM items WHERE id = ?1",
+        )?;
+        Ok(statement.query_row([id.id()], mapping_item).optional()?)
+    }
+
+    /// Returns every stored item, ordered by id.
+    pub fn mapping(&self) -> Result<Vec<MappingItem>, ArchiveError> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT id, name, examine, members, low_alch, high_alch, value, buy_limit, icon FROM items ORDER BY id",
+        )?;
+        let items = statement.query_map([], mapping_item)?.collect::<Result<_, _>>()?;
+        Ok(items)
+    }
+
+    /// Returns the most recent stored trades of an item, as a latest prices entry.
+    pub fn latest(&self, item_id: ItemId) -> Result<Option<LatestItem>, ArchiveError> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT price, time FROM trades WHERE item_id = ?1 AND side = ?2 ORDER BY time DESC LIMIT 1",
+        )?;
+        let mut last_trade = |side: &str| -> Result<Option<(Coins, DateTime<Utc>)>, rusqlite::Error> {
+            statement.query_row(params![item_id.id(), side], |row| Ok((coins(row, 0)?, timestamp(row, 1)?))).optional()
+        };
+        let (high, low) = (last_trade("high")?, last_trade("low")?);
+        if high.is_none() && low.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(LatestItem {
+            high: high.map(|(price, _)| price),
+            low: low.map(|(price, _)| price),
+            high_time: high.map(|(_, time)| time),
+            low_time: low.map(|(_, time)| time),
+        }))
+    }
+
+    /// Returns the interval prices of an item with timestamps in `range`, oldest first.
+    pub fn timeseries(&self, item_id: ItemId, interval: Timestep, range: Range<DateTime<Utc>>) -> Result<Vec<TimeseriesItem>, ArchiveError> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT timestamp, avg_high_price, high_price_volume, avg_low_price, low_price_volume FROM prices
+             WHERE item_id = ?1 AND interval = ?2 AND timestamp >= ?3 AND timestamp < ?4
+             ORDER BY timestamp",
+        )?;
+        let params = params![item_id.id(), interval.as_ref(), range.start.timestamp(), range.end.timestamp()];
+        let items = statement
+            .query_map(params, |row| {
+                Ok(TimeseriesItem {
+                    timestamp: timestamp(row, 0)?,
+                    avg_high_price: row.get::<_, Option<u32>>(1)?.map(Coins::new),
+                    high_price_volume: row.get(2)?,
+                    avg_low_price: row.get::<_, Option<u32>>(3)?.map(Coins::new),
+                    low_price_volume: row.get(4)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(items)
+    }
+
+    /// Returns the stored snapshot of all items for one interval bucket.
+    pub fn snapshot(&self, interval: Timestep, timestamp: DateTime<Utc>) -> Result<Option<PricesResponseSuccess>, ArchiveError> {
+        let mut statement = self.connection.prepare_cached(
+            "SELECT item_id, avg_high_price, high_price_volume, avg_low_price, low_price_volume FROM prices
+             WHERE interval = ?1 AND timestamp = ?2",
+        )?;
+        let data: HashMap<ItemId, PriceItem> = statement
+            .query_map(params![interval.as_ref(), timestamp.timestamp()], |row| {
+                let item = PriceItem {
+                    avg_high_price: row.get::<_, Option<u32>>(1)?.map(Coins::new),
+                    high_price_volume: row.get(2)?,
+                    avg_low_price: row.get::<_, Option<u32>>(3)?.map(Coins::new),
+                    low_price_volume: row.get(4)?,
+                };
+                Ok((ItemId::new(row.get(0)?), item))
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok((!data.is_empty()).then_some(PricesResponseSuccess { data, timestamp }))
+    }
+
+    /// Returns the timestamps of the stored snapshots for an interval, oldest first.
+    pub fn snapshot_timestamps(&self, interval: Timestep) -> Result<Vec<DateTime<Utc>>, ArchiveError> {
+        let mut statement = self.connection.prepare_cached("SELECT DISTINCT timestamp FROM prices WHERE interval = ?1 ORDER BY timestamp")?;
+        let timestamps = statement.query_map([interval.as_ref()], |row| timestamp(row, 0))?.collect::<Result<_, _>>()?;
+        Ok(timestamps)
+    }
+
+    /// Returns the intervals with stored prices.
+    pub fn intervals(&self) -> Result<Vec<Timestep>, ArchiveError> {
+        let mut statement = self.connection.prepare_cached("SELECT DISTINCT interval FROM prices")?;
+        let stored: Vec<String> = statement.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+        Ok(INTERVALS.into_iter().filter(|interval| stored.iter().any(|stored| stored == interval.as_ref())).collect())
+    }
+}
+
+fn schema_version(connection: &Connection) -> Result<usize, ArchiveError> {
+    let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    Ok(usize::try_from(version).unwrap_or(0))
+}
+
+fn migrate(connection: &mut Connection) -> Result<(), ArchiveError> {
+    let version = schema_version(connection)?;
+    if version > MIGRATIONS.len() {
+        return Err(ArchiveError::UnsupportedVersion { found: version, supported: MIGRATIONS.len() });
+    }
+    let transaction = connection.transaction()?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+fn mapping_item(row: &Row) -> Result<MappingItem, rusqlite::Error> {
+    Ok(MappingItem {
+        id: ItemId::new(row.get(0)?),
+        name: row.get(1)?,
+        examine: row.get(2)?,
+        members: row.get(3)?,
+        low_alch: row.get::<_, Option<u32>>(4)?.map(Coins::new),
+        high_alch: row.get::<_, Option<u32>>(5)?.map(Coins::new),
+        value: row.get::<_, Option<u32>>(6)?.map(Coins::new),
+        limit: row.get(7)?,
+        icon: row.get(8)?,
+    })
+}
+
+fn coins(row: &Row, index: usize) -> Result<Coins, rusqlite::Error> {
+    row.get(index).map(Coins::new)
+}
+
+fn timestamp(row: &Row, index: usize) -> Result<DateTime<Utc>, rusqlite::Error> {
+    let seconds: i64 = row.get(index)?;
+    DateTime::from_timestamp(seconds, 0).ok_or(rusqlite::Error::IntegralValueOutOfRange(index, seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::archive::{ArchiveError, PriceArchive, MIGRATIONS};
+    use crate::endpoints::latest::LatestItem;
+    use crate::endpoints::mapping::MappingItem;
+    use crate::endpoints::prices::{PriceItem, PricesResponseSuccess};
+    use crate::endpoints::timeseries::{TimeseriesItem, Timestep};
+    use crate::types::{Coins, ItemId};
+    use chrono::{DateTime, TimeZone, Utc};
+    use rusqlite::Connection;
+    use std::collections::HashMap;
+
+    const WHIP: ItemId = ItemId::new(4151);
+    const CHISEL: ItemId = ItemId::new(1755);
+
+    fn at(timestamp: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(timestamp, 0).unwrap()
+    }
+
+    fn mapping_item(id: ItemId, name: &str, high_alch: Option<u32>, limit: Option<u32>) -> MappingItem {
+        MappingItem {
+            id,
+            name: name.to_string(),
+            examine: format!("{}.", name),
+            members: true,
+            low_alch: None,
+            high_alch: high_alch.map(Coins::new),
+            value: Some(Coins::new(1)),
+            limit,
+            icon: format!("{}.png", name),
+        }
+    }
+
+    fn price_item(avg_high_price: Option<u32>, avg_low_price: u32, volume: u32) -> PriceItem {
+        PriceItem {
+            avg_high_price: avg_high_price.map(Coins::new),
+            high_price_volume: volume,
+            avg_low_price: Some(Coins::new(avg_low_price)),
+            low_price_volume: volume,
+        }
+    }
+
+    fn timeseries_item(timestamp: i64, avg_high_price: u32) -> TimeseriesItem {
+        TimeseriesItem {
+            timestamp: at(timestamp),
+            avg_high_price: Some(Coins::new(avg_high_price)),
+            high_price_volume: 10,
+            avg_low_price: None,
+            low_price_volume: 0,
+        }
+    }
+
+    #[test]
+    fn test_mapping_and_latest() {
+        let mut archive = PriceArchive::open_in_memory().unwrap();
+        assert_eq!(archive.schema_version().unwrap(), MIGRATIONS.len());
+
+        let mapping = vec![mapping_item(WHIP, "Abyssal whip", Some(72_000), Some(70)), mapping_item(CHISEL, "Chisel", None, None)];
+        archive.insert_mapping(&mapping).unwrap();
+        archive.insert_mapping(&mapping).unwrap();
+        assert_eq!(archive.mapping().unwrap(), vec![mapping[1].clone(), mapping[0].clone()]);
+
+        let mut changed = mapping[0].clone();
+        changed.limit = Some(100);
+        archive.insert_mapping(std::slice::from_ref(&changed)).unwrap();
+        assert_eq!(archive.item(WHIP).unwrap(), Some(changed));
+        assert_eq!(archive.item(ItemId::new(1)).unwrap(), None);
+
+        let whip = LatestItem {
+            high: Some(Coins::new(1_502_000)),
+            low: Some(Coins::new(1_490_123)),
+            high_time: Some(at(1760702384)),
+            low_time: Some(at(1760702344)),
+        };
+        let chisel = LatestItem { high: None, low: Some(Coins::new(2)), high_time: None, low_time: Some(at(1760702360)) };
+        let latest = HashMap::from([(WHIP, whip.clone()), (CHISEL, chisel.clone())]);
+        archive.insert_latest(&latest).unwrap();
+        archive.insert_latest(&latest).unwrap();
+        assert_eq!(archive.latest(WHIP).unwrap(), Some(whip.clone()));
+        assert_eq!(archive.latest(CHISEL).unwrap(), Some(chisel));
+        let trades: i64 = archive.connection().query_row("SELECT COUNT(*) FROM trades", [], |row| row.get(0)).unwrap();
+        assert_eq!(trades, 3);
+        assert_eq!(archive.latest(ItemId::new(1)).unwrap(), None);
+
+        // An older trade doesn't replace the most recent one.
+        let older = LatestItem { high: Some(Coins::new(1_400_000)), high_time: Some(at(1760700000)), ..whip.clone() };
+        archive.insert_latest(&HashMap::from([(WHIP, older)])).unwrap();
+        assert_eq!(archive.latest(WHIP).unwrap(), Some(whip));
+    }
+
+    #[test]
+    fn test_prices_and_timeseries() {
+        let mut archive = PriceArchive::open_in_memory().unwrap();
+
+        let snapshot = PricesResponseSuccess {
+            data: HashMap::from([(WHIP, price_item(Some(1_502_000), 1_490_123, 20)), (CHISEL, price_item(None, 2, 5))]),
+            timestamp: at(1760701800),
+        };
+        archive.insert_prices(Timestep::FiveMinutes, &snapshot).unwrap();
+        archive.insert_prices(Timestep::FiveMinutes, &snapshot).unwrap();
+        let hourly = PricesResponseSuccess { data: HashMap::from([(WHIP, price_item(Some(1_500_000), 1_480_000, 200))]), timestamp: at(1760698800) };
+        archive.insert_prices(Timestep::OneHour, &hourly).unwrap();
+
+        assert_eq!(archive.snapshot(Timestep::FiveMinutes, snapshot.timestamp).unwrap(), Some(snapshot.clone()));
+        assert_eq!(archive.snapshot(Timestep::OneHour, hourly.timestamp).unwrap(), Some(hourly));
+        assert_eq!(archive.snapshot(Timestep::SixHours, snapshot.timestamp).unwrap(), None);
+        assert_eq!(archive.snapshot_timestamps(Timestep::FiveMinutes).unwrap(), vec![snapshot.timestamp]);
+        assert_eq!(archive.intervals().unwrap(), vec![Timestep::FiveMinutes, Timestep::OneHour]);
+
+        // Timeseries points land in the same table, so the bucket shared with the snapshot is stored
+        // once, with the values ingested last.
+        let timeseries = vec![timeseries_item(1760701200, 1_498_000), timeseries_item(1760701500, 1_499_000), timeseries_item(1760701800, 1_501_000)];
+        archive.insert_timeseries(WHIP, Timestep::FiveMinutes, &timeseries).unwrap();
+        archive.insert_timeseries(WHIP, Timestep::FiveMinutes, &timeseries).unwrap();
+        assert_eq!(archive.timeseries(WHIP, Timestep::FiveMinutes, at(0)..at(i64::from(u32::MAX))).unwrap(), timeseries);
+
+        let window = archive.timeseries(WHIP, Timestep::FiveMinutes, at(1760701500)..at(1760701800)).unwrap();
+        assert_eq!(window, vec![timeseries[1].clone()]);
+        assert!(archive.timeseries(CHISEL, Timestep::OneHour, at(0)..at(i64::from(u32::MAX))).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrations() {
+        let path = std::env::temp_dir().join(format!("osrs-wiki-prices-archive-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        drop(PriceArchive::open(&path).unwrap());
+        // Reopening an up-to-date archive doesn't run migrations again.
+        let archive = PriceArchive::open(&path).unwrap();
+        assert_eq!(archive.schema_version().unwrap(), MIGRATIONS.len());
+        drop(archive);
+
+        let connection = Connection::open(&path).unwrap();
+        connection.pragma_update(None, "user_version", MIGRATIONS.len() + 1).unwrap();
+        let error = PriceArchive::from_connection(connection).unwrap_err();
+        assert!(matches!(error, ArchiveError::UnsupportedVersion { found, supported } if found == supported + 1));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,201 @@
+use crate::endpoints::prices::{PricesEndpoint, PricesResponseSuccess};
+use crate::endpoints::timeseries::Timestep;
+use crate::{Client, Error, ReqwestTransport, Transport};
+use chrono::{DateTime, TimeDelta, Utc};
+use futures_util::Stream;
+
+/// Where a [`Backfill`] continues from: the next bucket to fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint {
+    pub interval: Timestep,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub next: DateTime<Utc>,
+}
+
+impl Checkpoint {
+    /// Returns the checkpoint following an event.
+    pub fn after(interval: Timestep, event: &BackfillEvent) -> Self {
+        Self { interval, next: event.timestamp() + TimeDelta::seconds(interval.seconds()) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackfillEvent {
+    Snapshot(PricesResponseSuccess),
+    /// The API has no prices for the bucket starting at `timestamp`.
+    Missing { timestamp: DateTime<Utc> },
+}
+
+impl BackfillEvent {
+    /// The start of the bucket this event is for.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            BackfillEvent::Snapshot(snapshot) => snapshot.timestamp,
+            BackfillEvent::Missing { timestamp } => *timestamp,
+        }
+    }
+}
+
+/// Fetches the interval snapshots of a time range, oldest first.
+///
+/// The range is aligned to the interval: the first bucket is the first one starting at or after
+/// `start`, and the last is the last one starting before `end`. Buckets the API has no prices for
+/// are reported as [`BackfillEvent::Missing`]. Requests go through the client, so its rate
+/// limiter and retry policy apply.
+///
+/// A failed request ends the stream with the error. Save the [`Checkpoint::after`] the last
+/// processed event to continue later with [`Backfill::resume`]:
+///
+/// ```no_run
+/// # async fn example(client: osrs_wiki_prices::Client) -> Result<(), osrs_wiki_prices::Error> {
+/// use chrono::{TimeDelta, Utc};
+/// use futures_util::StreamExt;
+/// use osrs_wiki_prices::backfill::{Backfill, BackfillEvent};
+/// use osrs_wiki_prices::endpoints::timeseries::Timestep;
+///
+/// let end = Utc::now();
+/// let backfill = Backfill::new(client, Timestep::OneHour, end - TimeDelta::days(7), end);
+/// let mut events = std::pin::pin!(backfill.into_stream());
+/// while let Some(event) = events.next().await {
+///     match event? {
+///         BackfillEvent::Snapshot(snapshot) => println!("{}: {} items", snapshot.timestamp, snapshot.data.len()),
+///         BackfillEvent::Missing { timestamp } => println!("{}: no data", timestamp),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Backfill<T = ReqwestTransport> {
+    client: Client<T>,
+    interval: Timestep,
+    next: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+impl<T: Transport> Backfill<T> {
+    pub fn new(client: Client<T>, interval: Timestep, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        let mut next = interval.align(start);
+        if next < start {
+            next += TimeDelta::seconds(interval.seconds());
+        }
+        Self { client, interval, next, end }
+    }
+
+    /// Continues a backfill from a checkpoint up to `end`.
+    pub fn resume(client: Client<T>, checkpoint: Checkpoint, end: DateTime<Utc>) -> Self {
+        Self::new(client, checkpoint.interval, checkpoint.next, end)
+    }
+
+    /// Returns the checkpoint to resume this backfill from before any of it has run.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { interval: self.interval, next: self.next }
+    }
+
+    /// Returns the start of every bucket this backfill will fetch.
+    pub fn buckets(&self) -> impl Iterator<Item=DateTime<Utc>> + use<T> {
+        let (step, end) = (TimeDelta::seconds(self.interval.seconds()), self.end);
+        std::iter::successors(Some(self.next), move |timestamp| Some(*timestamp + step)).take_while(move |timestamp| *timestamp < end)
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item=Result<BackfillEvent, Error>> {
+        futures_util::stream::unfold(Some(self), |backfill| async move {
+            let mut backfill = backfill?;
+            let timestamp = backfill.next;
+            if timestamp >= backfill.end {
+                return None;
+            }
+            match backfill.client.prices(backfill.interval, Some(timestamp)).await {
+                Ok(snapshot) => {
+                    backfill.next = timestamp + TimeDelta::seconds(backfill.interval.seconds());
+                    let event = if snapshot.data.is_empty() {
+                        BackfillEvent::Missing { timestamp }
+                    } else {
+                        BackfillEvent::Snapshot(snapshot)
+                    };
+                    Some((Ok(event), Some(backfill)))
+                }
+                Err(error) => Some((Err(error), None)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backfill::{Backfill, BackfillEvent, Checkpoint};
+    use crate::endpoints::timeseries::Timestep;
+    use crate::transport::{MemoryTransport, Response};
+    use crate::types::ItemId;
+    use crate::{Client, Error, RetryPolicy};
+    use chrono::{DateTime, TimeZone, Utc};
+    use futures_util::StreamExt;
+    use reqwest::StatusCode;
+
+    fn at(timestamp: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(timestamp, 0).unwrap()
+    }
+
+    fn snapshot(timestamp: i64) -> Response {
+        Response::json(format!(
+            r#"{{"data":{{"4151":{{"avgHighPrice":1500000,"highPriceVolume":5,"avgLowPrice":1490000,"lowPriceVolume":4}}}},"timestamp":{}}}"#,
+            timestamp
+        ))
+    }
+
+    fn client(transport: MemoryTransport) -> Client<MemoryTransport> {
+        Client::builder()
+            .user_agent("test")
+            .retry_policy(RetryPolicy::none())
+            .no_rate_limit()
+            .build_with_transport(transport)
+    }
+
+    #[tokio::test]
+    async fn test_backfill() {
+        let transport = MemoryTransport::new()
+            .with_response("/5m?timestamp=1760701800", snapshot(1760701800))
+            .with_json("/5m?timestamp=1760702100", r#"{"data":{},"timestamp":1760702100}"#)
+            .with_response("/5m?timestamp=1760702400", snapshot(1760702400));
+        // Starts mid-bucket and ends on a bucket boundary, which is excluded.
+        let backfill = Backfill::new(client(transport.clone()), Timestep::FiveMinutes, at(1760701700), at(1760702700));
+        assert_eq!(backfill.buckets().collect::<Vec<_>>(), vec![at(1760701800), at(1760702100), at(1760702400)]);
+        assert_eq!(backfill.checkpoint(), Checkpoint { interval: Timestep::FiveMinutes, next: at(1760701800) });
+
+        let events: Vec<BackfillEvent> = backfill.into_stream().map(Result::unwrap).collect().await;
+        assert_eq!(events.len(), 3);
+        match &events[0] {
+            BackfillEvent::Snapshot(snapshot) => assert!(snapshot.data.contains_key(&ItemId::new(4151))),
+            event => panic!("Unexpected event {:?}", event),
+        }
+        assert_eq!(events[1], BackfillEvent::Missing { timestamp: at(1760702100) });
+        assert_eq!(events.iter().map(BackfillEvent::timestamp).collect::<Vec<_>>(), vec![at(1760701800), at(1760702100), at(1760702400)]);
+        let urls: Vec<String> = transport.requests().into_iter().map(|request| request.url).collect();
+        assert!(urls[0].ends_with("/5m?timestamp=1760701800"));
+        assert!(urls[2].ends_with("/5m?timestamp=1760702400"));
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_error() {
+        let transport = MemoryTransport::new()
+            .with_response("/1h?timestamp=1760691600", snapshot(1760691600))
+            .with_response("/1h?timestamp=1760695200", Response::new(StatusCode::BAD_GATEWAY, "bad gateway"));
+        let (start, end) = (at(1760691600), at(1760702400));
+        let mut events = std::pin::pin!(Backfill::new(client(transport), Timestep::OneHour, start, end).into_stream());
+
+        let event = events.next().await.unwrap().unwrap();
+        let checkpoint = Checkpoint::after(Timestep::OneHour, &event);
+        assert_eq!(checkpoint.next, at(1760695200));
+        let error = events.next().await.unwrap().unwrap_err();
+        assert!(matches!(error, Error::Status { status: StatusCode::BAD_GATEWAY, .. }));
+        assert!(events.next().await.is_none());
+
+        let checkpoint: Checkpoint = serde_json::from_str(&serde_json::to_string(&checkpoint).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(checkpoint).unwrap(), serde_json::json!({ "interval": "1h", "next": 1760695200 }));
+        let transport = MemoryTransport::new()
+            .with_response("/1h?timestamp=1760695200", snapshot(1760695200))
+            .with_response("/1h?timestamp=1760698800", snapshot(1760698800));
+        let resumed = Backfill::resume(client(transport), checkpoint, end);
+        let timestamps: Vec<DateTime<Utc>> = resumed.into_stream().map(|event| event.unwrap().timestamp()).collect().await;
+        assert_eq!(timestamps, vec![at(1760695200), at(1760698800)]);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::endpoints::latest::{LatestEndpoint, LatestItem};
+use crate::endpoints::mapping::{MappingEndpoint, MappingItem};
+use crate::endpoints::prices::five_minutes::PricesFiveMinutesEndpoint;
+use crate::endpoints::prices::one_hour::PricesOneHourEndpoint;
+use crate::endpoints::prices::{PricesEndpoint, PricesResponseSuccess};
+use crate::endpoints::timeseries::{TimeseriesEndpoint, TimeseriesItem, Timestep};
+use crate::types::ItemId;
+use crate::{ApiEndpoint, Client, ClientNewError, Error, ReqwestTransport, Transport};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BlockingClientNewError {
+    #[error(transparent)]
+    ClientError(#[from] ClientNewError),
+    #[error("Failed to create the runtime: {0}")]
+    RuntimeError(#[from] std::io::Error),
+}
+
+/// A synchronous wrapper around [`Client`], driving requests on its own single-threaded runtime.
+///
+/// Its methods block the calling thread and must not be called from within an async runtime.
+pub struct BlockingClient<T = ReqwestTransport> {
+    client: Client<T>,
+    runtime: tokio::runtime::Runtime,
+}
+
+impl BlockingClient {
+    pub fn try_new(user_agent: Cow<str>, api_endpoint: ApiEndpoint) -> Result<Self, BlockingClientNewError> {
+        Self::from_client(Client::try_new(user_agent, api_endpoint)?)
+    }
+}
+
+impl<T: Transport> BlockingClient<T> {
+    pub fn from_client(client: Client<T>) -> Result<Self, BlockingClientNewError> {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { client, runtime })
+    }
+
+    pub fn client(&self) -> &Client<T> {
+        &self.client
+    }
+
+    pub fn latest(&self) -> Result<HashMap<ItemId, LatestItem>, Error> {
+        self.runtime.block_on(self.client.latest())
+    }
+
+    pub fn latest_by_id(&self, id: ItemId) -> Result<LatestItem, Error> {
+        self.runtime.block_on(self.client.latest_by_id(id))
+    }
+
+    pub fn mapping(&self) -> Result<Vec<MappingItem>, Error> {
+        self.runtime.block_on(self.client.mapping())
+    }
+
+    pub fn timeseries(&self, id: ItemId, timestep: Timestep) -> Result<Vec<TimeseriesItem>, Error> {
+        self.runtime.block_on(self.client.timeseries(id, timestep))
+    }
+
+    pub fn prices(&self, interval: Timestep, timestamp: Option<DateTime<Utc>>) -> Result<PricesResponseSuccess, Error> {
+        self.runtime.block_on(self.client.prices(interval, timestamp))
+    }
+
+    pub fn prices_five_minutes(&self) -> Result<PricesResponseSuccess, Error> {
+        self.runtime.block_on(self.client.prices_five_minutes())
+    }
+
+    pub fn prices_five_minutes_with_timestamp(&self, timestamp: DateTime<Utc>) -> Result<PricesResponseSuccess, Error> {
+        self.runtime.block_on(self.client.prices_five_minutes_with_timestamp(timestamp))
+    }
+
+    pub fn prices_one_hour(&self) -> Result<PricesResponseSuccess, Error> {
+        self.runtime.block_on(self.client.prices_one_hour())
+    }
+
+    pub fn prices_one_hour_with_timestamp(&self, timestamp: DateTime<Utc>) -> Result<PricesResponseSuccess, Error> {
+        self.runtime.block_on(self.client.prices_one_hour_with_timestamp(timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::BlockingClient;
+    use crate::endpoints::timeseries::Timestep;
+    use crate::testing::get_test_client;
+    use crate::types::ItemId;
+    use crate::ApiEndpoint;
+
+    #[test]
+    fn test_blocking_client() {
+        let client = BlockingClient::from_client(get_test_client(ApiEndpoint::OldSchoolRuneScape)).unwrap();
+        assert!(!client.latest().unwrap().is_empty());
+        assert!(client.latest_by_id(ItemId::new(4151)).is_ok());
+        assert!(!client.mapping().unwrap().is_empty());
+        assert!(!client.timeseries(ItemId::new(4151), Timestep::FiveMinutes).unwrap().is_empty());
+        let prices = client.prices_one_hour().unwrap();
+        assert_eq!(prices.timestamp.timestamp() % 3600, 0);
+        assert!(client.prices(Timestep::OneDay, None).is_ok());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::runtime::FuturesTimer;
+use crate::{ApiEndpoint, Client, ClientNewError, RateLimiter, ReqwestTransport, ResponseCache, RetryPolicy, Timer, Transport, BASE_URL};
+use reqwest::header::HeaderMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builder for [`Client`], obtained through [`Client::builder`].
+///
+/// The timeout, proxy and default header settings only apply to the `reqwest::Client` built by
+/// this builder; they are ignored when a pre-built client is passed to [`ClientBuilder::http_client`]
+/// or a custom transport to [`ClientBuilder::build_with_transport`].
+pub struct ClientBuilder {
+    user_agent: Option<String>,
+    api_endpoint: ApiEndpoint,
+    base_url: String,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxies: Vec<reqwest::Proxy>,
+    default_headers: HeaderMap,
+    http_client: Option<reqwest::Client>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    cache: Option<ResponseCache>,
+    timer: Arc<dyn Timer>,
+}
+
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("user_agent", &self.user_agent)
+            .field("api_endpoint", &self.api_endpoint)
+            .field("base_url", &self.base_url)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("timeout", &self.timeout)
+            .field("proxies", &self.proxies)
+            .field("default_headers", &self.default_headers)
+            .field("http_client", &self.http_client)
+            .field("retry_policy", &self.retry_policy)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("cache", &self.cache)
+            .field("timer", &format_args!("dyn Timer"))
+            .finish()
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            user_agent: None,
+            api_endpoint: ApiEndpoint::OldSchoolRuneScape,
+            base_url: format!("https://{}", BASE_URL),
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            proxies: Vec::new(),
+            default_headers: HeaderMap::new(),
+            http_client: None,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: Some(RateLimiter::default()),
+            cache: None,
+            timer: Arc::new(FuturesTimer),
+        }
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn api_endpoint(mut self, api_endpoint: ApiEndpoint) -> Self {
+        self.api_endpoint = api_endpoint;
+        self
+    }
+
+    /// Sets the root URL the API endpoint path (`osrs`, `dmm`) is appended to.
+    ///
+    /// Defaults to `https://prices.runescape.wiki/api/v1`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a total timeout covering the whole request, from connecting until the body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Uses a pre-built `reqwest::Client` instead of building one.
+    ///
+    /// The user agent is expected to be configured on the given client.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Sets the policy used to retry transient failures. Use [`RetryPolicy::none`] to disable retries.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the limiter every request waits on before being sent.
+    ///
+    /// Clients use [`RateLimiter::default`] unless configured otherwise. Pass a clone of the same
+    /// limiter to several builders to share one budget between them.
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Disables client-side rate limiting.
+    pub fn no_rate_limit(mut self) -> Self {
+        self.rate_limiter = None;
+        self
+    }
+
+    /// Enables caching of `latest` and `mapping` responses.
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sets the timer used to wait between retries and for the rate limiter.
+    ///
+    /// Defaults to [`FuturesTimer`], which works on any executor.
+    pub fn timer(mut self, timer: impl Timer + 'static) -> Self {
+        self.timer = Arc::new(timer);
+        self
+    }
+
+    pub fn build(mut self) -> Result<Client, ClientNewError> {
+        let http_client = match self.http_client.take() {
+            Some(http_client) => http_client,
+            None => {
+                let user_agent = self.user_agent.take().ok_or(ClientNewError::MissingUserAgent)?;
+                let mut builder = reqwest::Client::builder()
+                    .user_agent(user_agent)
+                    .default_headers(std::mem::take(&mut self.default_headers));
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.read_timeout {
+                    builder = builder.read_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                for proxy in self.proxies.drain(..) {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+        Ok(self.build_with_transport(ReqwestTransport::new(http_client)))
+    }
+
+    /// Builds a client sending its requests through the given transport.
+    pub fn build_with_transport<T: Transport>(self, transport: T) -> Client<T> {
+        let base_url = format!("{}/{}", self.base_url.trim_end_matches('/'), self.api_endpoint);
+        Client {
+            transport,
+            base_url,
+            retry_policy: self.retry_policy,
+            rate_limiter: self.rate_limiter,
+            cache: self.cache,
+            timer: self.timer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ApiEndpoint, Client, ClientNewError};
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use std::time::Duration;
+
+    #[test]
+    fn test_builder_defaults() {
+        let client = Client::builder().user_agent("test_user_agent").build().unwrap();
+        assert_eq!(client.base_url(), "https://prices.runescape.wiki/api/v1/osrs");
+    }
+
+    #[test]
+    fn test_builder_debug() {
+        let debug = format!("{:?}", Client::builder().user_agent("test_user_agent"));
+        assert!(debug.starts_with("ClientBuilder {"));
+        assert!(debug.contains(r#"user_agent: Some("test_user_agent")"#));
+        assert!(debug.contains("timer: dyn Timer"));
+    }
+
+    #[test]
+    fn test_builder_base_url() {
+        let client = Client::builder()
+            .user_agent("test_user_agent")
+            .api_endpoint(ApiEndpoint::DeadmanArmageddon)
+            .base_url("http://localhost:8080/api/v1/")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "http://localhost:8080/api/v1/dmm");
+    }
+
+    #[test]
+    fn test_builder_options() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-mirror-token", HeaderValue::from_static("secret"));
+        let client = Client::builder()
+            .user_agent("test_user_agent")
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(30))
+            .proxy(reqwest::Proxy::all("http://localhost:3128").unwrap())
+            .default_headers(headers)
+            .build();
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_builder_missing_user_agent() {
+        let result = Client::builder().build();
+        assert!(matches!(result, Err(ClientNewError::MissingUserAgent)));
+    }
+
+    #[test]
+    fn test_builder_http_client() {
+        let http_client = reqwest::Client::builder().user_agent("test_user_agent").build().unwrap();
+        let client = Client::builder().http_client(http_client).build();
+        assert!(client.is_ok());
+    }
+}
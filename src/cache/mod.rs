@@ -0,0 +1,221 @@
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CachedEndpoint {
+    Latest,
+    Mapping,
+}
+
+/// How long cached responses are served without contacting the API.
+///
+/// Once an entry is older than its TTL, the next call sends a conditional request and reuses the
+/// cached value if the API answers `304 Not Modified`.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub latest_ttl: Duration,
+    pub mapping_ttl: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            latest_ttl: Duration::ZERO,
+            mapping_ttl: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn ttl(&self, endpoint: CachedEndpoint) -> Duration {
+        match endpoint {
+            CachedEndpoint::Latest => self.latest_ttl,
+            CachedEndpoint::Mapping => self.mapping_ttl,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Calls answered from the cache without sending a request.
+    pub hits: u64,
+    /// Calls answered from the cache after the API confirmed it with `304 Not Modified`.
+    pub revalidations: u64,
+    /// Calls that downloaded and parsed a full response.
+    pub misses: u64,
+}
+
+struct CacheEntry {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    stored_at: Instant,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    stats: CacheStats,
+}
+
+/// An in-memory cache of parsed responses, shared by every clone of it.
+#[derive(Clone, Default)]
+pub struct ResponseCache {
+    config: CacheConfig,
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("config", &self.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            config,
+            state: Arc::new(Mutex::new(CacheState::default())),
+        }
+    }
+
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.state.lock().unwrap().stats
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    pub(crate) fn fresh<T: Clone + 'static>(&self, endpoint: CachedEndpoint, url: &str) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entries.get(url)?;
+        if entry.stored_at.elapsed() >= self.config.ttl(endpoint) {
+            return None;
+        }
+        let value = entry.value.downcast_ref::<T>()?.clone();
+        state.stats.hits += 1;
+        Some(value)
+    }
+
+    pub(crate) fn conditional_headers(&self, url: &str) -> HeaderMap {
+        let state = self.state.lock().unwrap();
+        let mut headers = HeaderMap::new();
+        if let Some(entry) = state.entries.get(url) {
+            if let Some(etag) = &entry.etag {
+                headers.insert(IF_NONE_MATCH, etag.clone());
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+            }
+        }
+        headers
+    }
+
+    pub(crate) fn revalidate<T: Clone + 'static>(&self, url: &str) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let entry = state.entries.get_mut(url)?;
+        let value = entry.value.downcast_ref::<T>()?.clone();
+        entry.stored_at = Instant::now();
+        state.stats.revalidations += 1;
+        Some(value)
+    }
+
+    pub(crate) fn store<T: Send + Sync + 'static>(&self, url: &str, headers: &HeaderMap, value: T) {
+        let entry = CacheEntry {
+            etag: headers.get(ETAG).cloned(),
+            last_modified: headers.get(LAST_MODIFIED).cloned(),
+            stored_at: Instant::now(),
+            value: Arc::new(value),
+        };
+        let mut state = self.state.lock().unwrap();
+        state.entries.insert(url.to_string(), entry);
+        state.stats.misses += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{CacheConfig, CacheStats, CachedEndpoint, ResponseCache};
+    use crate::endpoints::latest::LatestEndpoint;
+    use crate::endpoints::mapping::MappingEndpoint;
+    use crate::testing::{http_response, start_mock_server};
+    use crate::Client;
+    use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_NONE_MATCH};
+    use std::time::Duration;
+
+    const MAPPING: &str = r#"[{"examine":"A weapon from the abyss.","id":4151,"members":true,"lowalch":48000,"limit":70,"value":120001,"highalch":72000,"icon":"Abyssal whip.png","name":"Abyssal whip"}]"#;
+
+    fn cached_client(base_url: &str, cache: ResponseCache) -> Client {
+        Client::builder()
+            .user_agent("osrs-wiki-prices-rs")
+            .base_url(base_url)
+            .cache(cache)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cache_entries() {
+        let cache = ResponseCache::new(CacheConfig { latest_ttl: Duration::ZERO, mapping_ttl: Duration::from_secs(60) });
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+        cache.store("/mapping", &headers, vec![1u32, 2, 3]);
+        cache.store("/latest", &HeaderMap::new(), vec![4u32]);
+
+        assert_eq!(cache.fresh::<Vec<u32>>(CachedEndpoint::Mapping, "/mapping"), Some(vec![1, 2, 3]));
+        assert_eq!(cache.fresh::<String>(CachedEndpoint::Mapping, "/mapping"), None);
+        assert_eq!(cache.fresh::<Vec<u32>>(CachedEndpoint::Latest, "/latest"), None);
+        assert_eq!(cache.conditional_headers("/mapping").get(IF_NONE_MATCH).unwrap(), "\"abc\"");
+        assert!(cache.conditional_headers("/latest").is_empty());
+        assert_eq!(cache.revalidate::<Vec<u32>>("/latest"), Some(vec![4]));
+        assert_eq!(cache.stats(), CacheStats { hits: 1, revalidations: 1, misses: 2 });
+    }
+
+    #[tokio::test]
+    async fn test_mapping_revalidated() {
+        let server = start_mock_server(vec![
+            http_response(200, &[("etag", "\"v1\""), ("last-modified", "Wed, 11 Oct 2023 21:20:00 GMT")], MAPPING),
+            http_response(304, &[("etag", "\"v1\"")], ""),
+        ])
+        .await;
+        let cache = ResponseCache::new(CacheConfig { mapping_ttl: Duration::ZERO, ..CacheConfig::default() });
+        let client = cached_client(&server.base_url, cache.clone());
+
+        let first = client.mapping().await.unwrap();
+        let second = client.mapping().await.unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].name, "Abyssal whip");
+        assert_eq!(cache.stats(), CacheStats { hits: 0, revalidations: 1, misses: 1 });
+
+        let requests = server.requests.lock().unwrap();
+        assert!(!requests[0].to_lowercase().contains("if-none-match"));
+        assert!(requests[1].to_lowercase().contains("if-none-match: \"v1\""));
+        assert!(requests[1].to_lowercase().contains("if-modified-since: wed, 11 oct 2023 21:20:00 gmt"));
+    }
+
+    #[tokio::test]
+    async fn test_latest_served_within_ttl() {
+        let body = r#"{"data":{"4151":{"high":1500000,"highTime":1697059200,"low":1490000,"lowTime":1697059100}}}"#;
+        let server = start_mock_server(vec![http_response(200, &[], body)]).await;
+        let cache = ResponseCache::new(CacheConfig { latest_ttl: Duration::from_secs(60), ..CacheConfig::default() });
+        let client = cached_client(&server.base_url, cache.clone());
+
+        for _ in 0..3 {
+            let latest = client.latest().await.unwrap();
+            assert_eq!(latest.len(), 1);
+        }
+        assert_eq!(cache.stats(), CacheStats { hits: 2, revalidations: 0, misses: 1 });
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::endpoints::mapping::MappingItem;
+use crate::types::{Coins, ItemId};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// The differences between two snapshots of the item mapping, ordered by item id.
+///
+/// Serializes to a JSON changelog; [`Display`] renders it as one line per item:
+///
+/// ```text
+/// + Abyssal whip (4151)
+/// - Dwarf remains (0)
+/// ~ Cannonball (2): limit 11,000 -> 7,000; value 5 -> 6
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MappingDiff {
+    pub added: Vec<MappingItem>,
+    pub removed: Vec<MappingItem>,
+    pub changed: Vec<ItemChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ItemChange {
+    pub id: ItemId,
+    /// The item name in the newer snapshot.
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum FieldChange {
+    Name { old: String, new: String },
+    Examine { old: String, new: String },
+    Members { old: bool, new: bool },
+    Limit { old: Option<u32>, new: Option<u32> },
+    Value { old: Option<Coins>, new: Option<Coins> },
+    LowAlch { old: Option<Coins>, new: Option<Coins> },
+    HighAlch { old: Option<Coins>, new: Option<Coins> },
+    Icon { old: String, new: String },
+}
+
+/// Compares two mapping snapshots by item id.
+pub fn diff_mappings(old: &[MappingItem], new: &[MappingItem]) -> MappingDiff {
+    let old: BTreeMap<ItemId, &MappingItem> = old.iter().map(|item| (item.id, item)).collect();
+    let new: BTreeMap<ItemId, &MappingItem> = new.iter().map(|item| (item.id, item)).collect();
+
+    let mut diff = MappingDiff::default();
+    for (id, new_item) in &new {
+        match old.get(id) {
+            None => diff.added.push((*new_item).clone()),
+            Some(old_item) => {
+                let changes = field_changes(old_item, new_item);
+                if !changes.is_empty() {
+                    diff.changed.push(ItemChange { id: *id, name: new_item.name.clone(), changes });
+                }
+            }
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|(id, _)| !new.contains_key(id))
+        .map(|(_, item)| (*item).clone())
+        .collect();
+    diff
+}
+
+fn field_changes(old: &MappingItem, new: &MappingItem) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    if old.name != new.name {
+        changes.push(FieldChange::Name { old: old.name.clone(), new: new.name.clone() });
+    }
+    if old.examine != new.examine {
+        changes.push(FieldChange::Examine { old: old.examine.clone(), new: new.examine.clone() });
+    }
+    if old.members != new.members {
+        changes.push(FieldChange::Members { old: old.members, new: new.members });
+    }
+    if old.limit != new.limit {
+        changes.push(FieldChange::Limit { old: old.limit, new: new.limit });
+    }
+    if old.value != new.value {
+        changes.push(FieldChange::Value { old: old.value, new: new.value });
+    }
+    if old.low_alch != new.low_alch {
+        changes.push(FieldChange::LowAlch { old: old.low_alch, new: new.low_alch });
+    }
+    if old.high_alch != new.high_alch {
+        changes.push(FieldChange::HighAlch { old: old.high_alch, new: new.high_alch });
+    }
+    if old.icon != new.icon {
+        changes.push(FieldChange::Icon { old: old.icon.clone(), new: new.icon.clone() });
+    }
+    changes
+}
+
+impl MappingDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl FieldChange {
+    /// The name of the changed field, as used in the serialized changelog.
+    pub fn field(&self) -> &'static str {
+        match self {
+            FieldChange::Name { .. } => "name",
+            FieldChange::Examine { .. } => "examine",
+            FieldChange::Members { .. } => "members",
+            FieldChange::Limit { .. } => "limit",
+            FieldChange::Value { .. } => "value",
+            FieldChange::LowAlch { .. } => "low_alch",
+            FieldChange::HighAlch { .. } => "high_alch",
+            FieldChange::Icon { .. } => "icon",
+        }
+    }
+}
+
+/// Formats an optional amount with digit grouping, for coin values and buy limits alike.
+struct Amount(Option<Coins>);
+
+impl Display for Amount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(coins) => write!(f, "{:#}", coins),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", self.field())?;
+        match self {
+            FieldChange::Name { old, new } | FieldChange::Examine { old, new } | FieldChange::Icon { old, new } => {
+                write!(f, "{:?} -> {:?}", old, new)
+            }
+            FieldChange::Members { old, new } => write!(f, "{} -> {}", old, new),
+            FieldChange::Limit { old, new } => {
+                let old = Amount(old.map(Coins::new));
+                let new = Amount(new.map(Coins::new));
+                write!(f, "{} -> {}", old, new)
+            }
+            FieldChange::Value { old, new } | FieldChange::LowAlch { old, new } | FieldChange::HighAlch { old, new } => {
+                write!(f, "{} -> {}", Amount(*old), Amount(*new))
+            }
+        }
+    }
+}
+
+impl Display for ItemChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "~ {} ({}): ", self.name, self.id.id())?;
+        for (index, change) in self.changes.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for MappingDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for item in &self.added {
+            writeln!(f, "+ {} ({})", item.name, item.id.id())?;
+        }
+        for item in &self.removed {
+            writeln!(f, "- {} ({})", item.name, item.id.id())?;
+        }
+        for change in &self.changed {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::{diff_mappings, FieldChange, MappingDiff};
+    use crate::endpoints::mapping::MappingItem;
+    use crate::types::{Coins, ItemId};
+
+    fn item(id: u32, name: &str, limit: u32, value: u32) -> MappingItem {
+        MappingItem {
+            examine: format!("{}.", name),
+            id: ItemId::new(id),
+            members: true,
+            low_alch: None,
+            limit: Some(limit),
+            value: Some(Coins::new(value)),
+            high_alch: None,
+            icon: format!("{}.png", name),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_mappings() {
+        let old = vec![
+            item(2, "Cannonball", 11_000, 5),
+            item(1755, "Chisel", 40, 1),
+            item(4151, "Abyssal whip", 70, 120_001),
+        ];
+        assert!(diff_mappings(&old, &old).is_empty());
+
+        let mut new = old.clone();
+        new.retain(|item| item.id != ItemId::new(1755));
+        let cannonball = new.iter_mut().find(|item| item.id == ItemId::new(2)).unwrap();
+        cannonball.limit = Some(7000);
+        cannonball.value = Some(Coins::new(6));
+        let mut added = cannonball.clone();
+        added.id = ItemId::new(30000);
+        added.name = "Granite cannonball".to_string();
+        new.push(added);
+
+        let diff = diff_mappings(&old, &new);
+        assert_eq!(diff.added.iter().map(|item| item.id).collect::<Vec<_>>(), vec![ItemId::new(30000)]);
+        assert_eq!(diff.removed.iter().map(|item| item.id).collect::<Vec<_>>(), vec![ItemId::new(1755)]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].id, ItemId::new(2));
+        assert_eq!(
+            diff.changed[0].changes,
+            vec![
+                FieldChange::Limit { old: Some(11000), new: Some(7000) },
+                FieldChange::Value { old: Some(Coins::new(5)), new: Some(Coins::new(6)) },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "+ Granite cannonball (30000)\n- Chisel (1755)\n~ Cannonball (2): limit 11,000 -> 7,000; value 5 -> 6\n"
+        );
+
+        let json = serde_json::to_value(&diff.changed[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "id": 2,
+                "name": "Cannonball",
+                "changes": [
+                    { "field": "limit", "old": 11000, "new": 7000 },
+                    { "field": "value", "old": 5, "new": 6 },
+                ],
+            })
+        );
+        let round_trip: MappingDiff = serde_json::from_str(&serde_json::to_string(&diff).unwrap()).unwrap();
+        assert_eq!(round_trip, diff);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::endpoints::mapping::MappingItem;
+use crate::types::{Coins, ItemId};
+use std::collections::HashMap;
+
+mod diff;
+mod store;
+
+pub use diff::{diff_mappings, FieldChange, ItemChange, MappingDiff};
+pub use store::{CatalogSnapshot, CatalogStore, CatalogStoreError};
+
+/// Minimum score for an item to be returned by [`ItemCatalog::search`].
+const MIN_SEARCH_SCORE: f64 = 0.7;
+
+/// An index over the item mapping for lookups by id and name, fuzzy search and autocompletion.
+#[derive(Debug, Clone, Default)]
+pub struct ItemCatalog {
+    items: Vec<MappingItem>,
+    by_id: HashMap<ItemId, usize>,
+    by_name: HashMap<String, usize>,
+    by_lowercase_name: HashMap<String, usize>,
+    /// Lowercase names with their item index, sorted for prefix lookups.
+    sorted_names: Vec<(String, usize)>,
+    /// Lowercase name tokens per item, used for fuzzy matching.
+    tokens: Vec<Vec<String>>,
+}
+
+/// Filters for catalog queries. Unset fields do not filter.
+#[derive(Debug, Clone, Default)]
+pub struct CatalogFilter {
+    pub members: Option<bool>,
+    pub min_limit: Option<u32>,
+    pub max_limit: Option<u32>,
+    pub min_value: Option<Coins>,
+    pub max_value: Option<Coins>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch<'a> {
+    pub item: &'a MappingItem,
+    /// Similarity between the query and the item name, from 0 to 1.
+    pub score: f64,
+}
+
+impl CatalogFilter {
+    pub fn matches(&self, item: &MappingItem) -> bool {
+        self.members.is_none_or(|members| item.members == members)
+            && self.min_limit.is_none_or(|min| item.limit.is_some_and(|limit| limit >= min))
+            && self.max_limit.is_none_or(|max| item.limit.is_some_and(|limit| limit <= max))
+            && self.min_value.is_none_or(|min| item.value.is_some_and(|value| value >= min))
+            && self.max_value.is_none_or(|max| item.value.is_some_and(|value| value <= max))
+    }
+}
+
+impl ItemCatalog {
+    pub fn new(mut items: Vec<MappingItem>) -> Self {
+        items.sort_by_key(|item| item.id);
+        let mut catalog = Self {
+            by_id: HashMap::with_capacity(items.len()),
+            by_name: HashMap::with_capacity(items.len()),
+            by_lowercase_name: HashMap::with_capacity(items.len()),
+            sorted_names: Vec::with_capacity(items.len()),
+            tokens: Vec::with_capacity(items.len()),
+            items: Vec::new(),
+        };
+        for (index, item) in items.iter().enumerate() {
+            let lowercase_name = item.name.to_lowercase();
+            catalog.by_id.insert(item.id, index);
+            catalog.by_name.entry(item.name.clone()).or_insert(index);
+            catalog.by_lowercase_name.entry(lowercase_name.clone()).or_insert(index);
+            catalog.tokens.push(tokenize(&lowercase_name));
+            catalog.sorted_names.push((lowercase_name, index));
+        }
+        catalog.sorted_names.sort();
+        catalog.items = items;
+        catalog
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns all items, ordered by id.
+    pub fn items(&self) -> &[MappingItem] {
+        &self.items
+    }
+
+    pub fn into_items(self) -> Vec<MappingItem> {
+        self.items
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<&MappingItem> {
+        self.by_id.get(&id).map(|index| &self.items[*index])
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&MappingItem> {
+        self.by_name.get(name).map(|index| &self.items[*index])
+    }
+
+    pub fn by_name_ignore_case(&self, name: &str) -> Option<&MappingItem> {
+        self.by_lowercase_name.get(&name.trim().to_lowercase()).map(|index| &self.items[*index])
+    }
+
+    /// Resolves user input to an item: an item id, an exact name (ignoring case), or else the best
+    /// fuzzy match.
+    pub fn resolve(&self, query: &str) -> Option<&MappingItem> {
+        if let Ok(id) = query.trim().parse::<u32>() {
+            return self.get(ItemId::new(id));
+        }
+        self.by_name_ignore_case(query)
+            .or_else(|| self.search(query, &CatalogFilter::default(), 1).first().map(|found| found.item))
+    }
+
+    pub fn filter(&self, filter: &CatalogFilter) -> Vec<&MappingItem> {
+        self.items.iter().filter(|item| filter.matches(item)).collect()
+    }
+
+    /// Returns items whose name starts with `prefix`, ignoring case, in alphabetical order.
+    pub fn autocomplete(&self, prefix: &str, limit: usize) -> Vec<&MappingItem> {
+        let prefix = prefix.trim().to_lowercase();
+        let start = self.sorted_names.partition_point(|(name, _)| name.as_str() < prefix.as_str());
+        self.sorted_names[start..]
+            .iter()
+            .take_while(|(name, _)| name.starts_with(&prefix))
+            .take(limit)
+            .map(|(_, index)| &self.items[*index])
+            .collect()
+    }
+
+    /// Searches item names with typo-tolerant matching, returning the best matches first.
+    ///
+    /// Each word of the query is matched against the closest word of the name, so abbreviations
+    /// and misspellings such as "abby whip" still find "Abyssal whip".
+    pub fn search(&self, query: &str, filter: &CatalogFilter, limit: usize) -> Vec<SearchMatch<'_>> {
+        let query_tokens = tokenize(&query.to_lowercase());
+        if query_tokens.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<SearchMatch> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| filter.matches(item))
+            .filter_map(|(index, item)| {
+                let score = score(&query_tokens, &self.tokens[index]);
+                (score >= MIN_SEARCH_SCORE).then_some(SearchMatch { item, score })
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.item.name.len().cmp(&b.item.name.len()))
+                .then(a.item.id.cmp(&b.item.id))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+impl From<Vec<MappingItem>> for ItemCatalog {
+    fn from(items: Vec<MappingItem>) -> Self {
+        Self::new(items)
+    }
+}
+
+fn tokenize(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn token_score(query_token: &str, name_token: &str) -> f64 {
+    if query_token == name_token {
+        1.0
+    } else if name_token.starts_with(query_token) {
+        0.95
+    } else {
+        strsim::jaro_winkler(query_token, name_token)
+    }
+}
+
+fn score(query_tokens: &[String], name_tokens: &[String]) -> f64 {
+    if query_tokens == name_tokens {
+        return 1.0;
+    }
+    let matched: f64 = query_tokens
+        .iter()
+        .map(|query_token| name_tokens.iter().map(|name_token| token_score(query_token, name_token)).fold(0.0, f64::max))
+        .sum();
+    let coverage = matched / query_tokens.len() as f64;
+    // Slightly prefer names without words the query does not mention.
+    let extra_tokens = name_tokens.len().saturating_sub(query_tokens.len()) as f64;
+    (coverage - 0.02 * extra_tokens).min(0.99)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::{CatalogFilter, ItemCatalog};
+    use crate::endpoints::mapping::MappingItem;
+    use crate::types::{Coins, ItemId};
+
+    fn item(id: u32, name: &str, members: bool, limit: u32, value: u32) -> MappingItem {
+        MappingItem {
+            examine: format!("{}.", name),
+            id: ItemId::new(id),
+            members,
+            low_alch: None,
+            limit: Some(limit),
+            value: Some(Coins::new(value)),
+            high_alch: None,
+            icon: format!("{}.png", name),
+            name: name.to_string(),
+        }
+    }
+
+    fn catalog() -> ItemCatalog {
+        ItemCatalog::new(vec![
+            item(2, "Cannonball", true, 11_000, 5),
+            item(554, "Fire rune", false, 50_000, 4),
+            item(561, "Nature rune", false, 18_000, 180),
+            item(565, "Blood rune", true, 25_000, 400),
+            item(1755, "Chisel", false, 40, 1),
+            item(4151, "Abyssal whip", true, 70, 120_001),
+            item(11832, "Bandos chestplate", true, 8, 265_019),
+            item(12934, "Zulrah's scales", true, 30_000, 1),
+            item(13190, "Old school bond", false, 100, 0),
+            item(13265, "Abyssal dagger", true, 8, 115_000),
+            item(12006, "Abyssal tentacle", true, 70, 250_000),
+            item(4587, "Dragon scimitar", true, 70, 100_000),
+            item(1333, "Rune scimitar", false, 70, 25_600),
+        ])
+    }
+
+    #[test]
+    fn test_lookups() {
+        let catalog = catalog();
+        assert_eq!(catalog.len(), 13);
+        assert_eq!(catalog.get(ItemId::new(4151)).unwrap().name, "Abyssal whip");
+        assert!(catalog.get(ItemId::new(1)).is_none());
+        assert_eq!(catalog.by_name("Abyssal whip").unwrap().id, ItemId::new(4151));
+        assert!(catalog.by_name("abyssal whip").is_none());
+        assert_eq!(catalog.by_name_ignore_case(" ABYSSAL WHIP ").unwrap().id, ItemId::new(4151));
+        assert_eq!(catalog.resolve("4151").unwrap().name, "Abyssal whip");
+        assert_eq!(catalog.resolve("abby whip").unwrap().id, ItemId::new(4151));
+    }
+
+    #[test]
+    fn test_search() {
+        let catalog = catalog();
+        let filter = CatalogFilter::default();
+        for query in ["abby whip", "abyssal whip", "abysal wip", "whip"] {
+            let results = catalog.search(query, &filter, 5);
+            assert_eq!(results[0].item.id, ItemId::new(4151), "query {:?}", query);
+        }
+        let results = catalog.search("d scim", &filter, 5);
+        assert_eq!(results[0].item.name, "Dragon scimitar");
+        assert!(results.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        assert!(catalog.search("zzzzzz", &filter, 5).is_empty());
+        assert!(catalog.search("  ", &filter, 5).is_empty());
+
+        let filter = CatalogFilter { members: Some(false), ..CatalogFilter::default() };
+        let results = catalog.search("scimitar", &filter, 5);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.name, "Rune scimitar");
+    }
+
+    #[test]
+    fn test_autocomplete() {
+        let catalog = catalog();
+        let names: Vec<&str> = catalog.autocomplete("aby", 10).iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Abyssal dagger", "Abyssal tentacle", "Abyssal whip"]);
+        assert_eq!(catalog.autocomplete("Abyssal", 2).len(), 2);
+        assert!(catalog.autocomplete("xyz", 10).is_empty());
+    }
+
+    #[test]
+    fn test_filter() {
+        let catalog = catalog();
+        let filter = CatalogFilter { members: Some(true), min_limit: Some(70), max_value: Some(Coins::new(150_000)), ..CatalogFilter::default() };
+        let names: Vec<&str> = catalog.filter(&filter).iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Cannonball", "Blood rune", "Abyssal whip", "Dragon scimitar", "Zulrah's scales"]);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::catalog::ItemCatalog;
+use crate::endpoints::mapping::{MappingEndpoint, MappingItem};
+use crate::{Client, Error, Transport};
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// The item mapping as fetched at a point in time, in the format stored on disk.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CatalogSnapshot {
+    pub fetched_at: DateTime<Utc>,
+    pub items: Vec<MappingItem>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CatalogStoreError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to fetch the item mapping: {0}")]
+    Fetch(#[from] Error),
+}
+
+impl CatalogSnapshot {
+    pub fn new(items: Vec<MappingItem>, fetched_at: DateTime<Utc>) -> Self {
+        Self { fetched_at, items }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CatalogStoreError> {
+        let contents = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    /// Writes the snapshot to a temporary file next to `path` and renames it into place, so
+    /// readers never observe a partially written snapshot.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CatalogStoreError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        std::fs::write(&temp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    pub fn is_stale(&self, max_age: Duration, now: DateTime<Utc>) -> bool {
+        is_stale(Some(self.fetched_at), max_age, now)
+    }
+}
+
+/// An [`ItemCatalog`] persisted to disk, so services don't need to fetch the mapping on every
+/// start.
+///
+/// The store always serves the last catalog it loaded or fetched, and never refreshes on its own:
+/// the caller drives refreshes by awaiting [`CatalogStore::drive_refresh`], which fetches a new
+/// catalog once the current one is older than `max_age`. If that fails the stale copy keeps being
+/// served. To refresh in the background, spawn it on the runtime of your choice:
+///
+/// ```no_run
+/// # async fn example(client: osrs_wiki_prices::Client) -> Result<(), osrs_wiki_prices::catalog::CatalogStoreError> {
+/// use osrs_wiki_prices::catalog::CatalogStore;
+/// use std::time::Duration;
+///
+/// let store = CatalogStore::load("mapping.json", Duration::from_secs(24 * 60 * 60), &client).await?;
+/// tokio::spawn({
+///     let store = store.clone();
+///     async move { store.drive_refresh(&client).await }
+/// });
+/// let whip = store.catalog().resolve("abyssal whip").cloned();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CatalogStore {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    path: PathBuf,
+    max_age: Duration,
+    state: RwLock<State>,
+    refreshing: AtomicBool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    catalog: Arc<ItemCatalog>,
+    fetched_at: Option<DateTime<Utc>>,
+}
+
+impl CatalogStore {
+    /// Opens the store at `path`, loading the snapshot stored there if there is one. Without a
+    /// snapshot the store starts out empty and stale.
+    pub fn open(path: impl Into<PathBuf>, max_age: Duration) -> Result<Self, CatalogStoreError> {
+        let path = path.into();
+        let state = match CatalogSnapshot::load(&path) {
+            Ok(snapshot) => State {
+                catalog: Arc::new(ItemCatalog::new(snapshot.items)),
+                fetched_at: Some(snapshot.fetched_at),
+            },
+            Err(CatalogStoreError::Io(error)) if error.kind() == std::io::ErrorKind::NotFound => State::default(),
+            Err(error) => return Err(error),
+        };
+        Ok(Self {
+            inner: Arc::new(Inner { path, max_age, state: RwLock::new(state), refreshing: AtomicBool::new(false) }),
+        })
+    }
+
+    /// Opens the store at `path`, fetching the mapping only if no snapshot is stored yet.
+    ///
+    /// A stale snapshot is served as is; call [`CatalogStore::drive_refresh`] to update it.
+    pub async fn load<T: Transport>(
+        path: impl Into<PathBuf>,
+        max_age: Duration,
+        client: &Client<T>,
+    ) -> Result<Self, CatalogStoreError> {
+        let store = Self::open(path, max_age)?;
+        if store.fetched_at().is_none() {
+            store.refresh(client).await?;
+        }
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    pub fn max_age(&self) -> Duration {
+        self.inner.max_age
+    }
+
+    /// Returns the current catalog. Refreshes replace it rather than modifying it in place.
+    pub fn catalog(&self) -> Arc<ItemCatalog> {
+        self.inner.state.read().unwrap().catalog.clone()
+    }
+
+    /// Returns when the current catalog was fetched, or `None` if the store is empty.
+    pub fn fetched_at(&self) -> Option<DateTime<Utc>> {
+        self.inner.state.read().unwrap().fetched_at
+    }
+
+    pub fn is_stale(&self) -> bool {
+        is_stale(self.fetched_at(), self.inner.max_age, Utc::now())
+    }
+
+    /// Fetches the mapping, replaces the current catalog and saves it to disk.
+    ///
+    /// If fetching fails the current catalog is kept. If saving fails the new catalog is still
+    /// served, but the error is returned.
+    pub async fn refresh<T: Transport>(&self, client: &Client<T>) -> Result<(), CatalogStoreError> {
+        let items = client.mapping().await?;
+        let snapshot = CatalogSnapshot::new(items, Utc::now());
+        let saved = snapshot.save(&self.inner.path);
+        *self.inner.state.write().unwrap() = State {
+            catalog: Arc::new(ItemCatalog::new(snapshot.items)),
+            fetched_at: Some(snapshot.fetched_at),
+        };
+        saved
+    }
+
+    /// Refreshes the catalog if it is older than `max_age`, returning whether it was refreshed.
+    /// Nothing happens unless this future is awaited or spawned.
+    ///
+    /// Concurrent calls don't fetch twice: while one refresh is in flight, others return
+    /// `Ok(false)` right away.
+    pub async fn drive_refresh<T: Transport>(&self, client: &Client<T>) -> Result<bool, CatalogStoreError> {
+        if !self.is_stale() || self.inner.refreshing.swap(true, Ordering::AcqRel) {
+            return Ok(false);
+        }
+        let _guard = RefreshGuard(&self.inner.refreshing);
+        self.refresh(client).await.map(|_| true)
+    }
+}
+
+/// Clears the in-flight flag when a refresh ends, including when its future is dropped.
+struct RefreshGuard<'a>(&'a AtomicBool);
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+fn is_stale(fetched_at: Option<DateTime<Utc>>, max_age: Duration, now: DateTime<Utc>) -> bool {
+    match fetched_at {
+        Some(fetched_at) => (now - fetched_at).to_std().is_ok_and(|age| age >= max_age),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::{CatalogSnapshot, CatalogStore, CatalogStoreError};
+    use crate::endpoints::mapping::MappingEndpoint;
+    use crate::testing::{get_test_client, http_response, start_mock_server};
+    use crate::transport::{Request, Response, TransportError};
+    use crate::types::ItemId;
+    use crate::{ApiEndpoint, Client, RetryPolicy, Transport};
+    use chrono::{TimeDelta, Utc};
+    use futures_util::FutureExt;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("osrs-wiki-prices-catalog-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn test_load_fetches_and_saves() {
+        let path = temp_path("fetch");
+        let client = get_test_client(ApiEndpoint::OldSchoolRuneScape);
+
+        let store = CatalogStore::load(&path, DAY, &client).await.unwrap();
+        assert!(!store.is_stale());
+        assert_eq!(store.catalog().get(ItemId::new(4151)).unwrap().name, "Abyssal whip");
+
+        let snapshot = CatalogSnapshot::load(&path).unwrap();
+        assert_eq!(snapshot.items, client.mapping().await.unwrap());
+        assert_eq!(Some(snapshot.fetched_at), store.fetched_at());
+
+        let reopened = CatalogStore::open(&path, DAY).unwrap();
+        assert_eq!(reopened.catalog().len(), store.catalog().len());
+        assert_eq!(reopened.fetched_at(), store.fetched_at());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_drive_refresh() {
+        let path = temp_path("stale");
+        let client = get_test_client(ApiEndpoint::OldSchoolRuneScape);
+        let mapping = client.mapping().await.unwrap();
+        CatalogSnapshot::new(mapping[..1].to_vec(), Utc::now() - TimeDelta::days(2)).save(&path).unwrap();
+
+        let store = CatalogStore::open(&path, DAY).unwrap();
+        assert!(store.is_stale());
+        assert_eq!(store.catalog().len(), 1);
+
+        assert!(store.drive_refresh(&client).await.unwrap());
+        assert!(!store.is_stale());
+        assert_eq!(store.catalog().len(), mapping.len());
+        assert!(!store.drive_refresh(&client).await.unwrap());
+        assert_eq!(CatalogSnapshot::load(&path).unwrap().items, mapping);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dropped_refresh_does_not_block_later_refreshes() {
+        struct PendingTransport;
+
+        impl Transport for PendingTransport {
+            async fn get(&self, _request: Request) -> Result<Response, TransportError> {
+                std::future::pending().await
+            }
+        }
+
+        let path = temp_path("dropped");
+        let pending = Client::builder().user_agent("test").no_rate_limit().build_with_transport(PendingTransport);
+        let store = CatalogStore::open(&path, DAY).unwrap();
+        assert!(store.drive_refresh(&pending).now_or_never().is_none());
+
+        let client = get_test_client(ApiEndpoint::OldSchoolRuneScape);
+        assert!(store.drive_refresh(&client).await.unwrap());
+        assert!(!store.catalog().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stale_copy_is_kept_when_refresh_fails() {
+        let path = temp_path("fallback");
+        let fixture = get_test_client(ApiEndpoint::OldSchoolRuneScape);
+        let fetched_at = Utc::now() - TimeDelta::days(2);
+        let mapping = fixture.mapping().await.unwrap();
+        CatalogSnapshot::new(mapping.clone(), fetched_at).save(&path).unwrap();
+
+        let server = start_mock_server(vec![http_response(503, &[], "unavailable")]).await;
+        let client = Client::builder()
+            .user_agent("test")
+            .base_url(&server.base_url)
+            .retry_policy(RetryPolicy::none())
+            .no_rate_limit()
+            .build()
+            .unwrap();
+
+        let store = CatalogStore::open(&path, DAY).unwrap();
+        let error = store.drive_refresh(&client).await.unwrap_err();
+        assert!(matches!(error, CatalogStoreError::Fetch(_)));
+        assert!(store.is_stale());
+        assert_eq!(store.fetched_at(), Some(fetched_at));
+        assert_eq!(store.catalog().len(), mapping.len());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_open_without_snapshot() {
+        let store = CatalogStore::open(temp_path("missing"), DAY).unwrap();
+        assert!(store.catalog().is_empty());
+        assert!(store.fetched_at().is_none());
+        assert!(store.is_stale());
+    }
+}
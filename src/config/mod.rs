@@ -0,0 +1,458 @@
+use crate::alerts::{AlertRule, Condition};
+use crate::analysis::flips::FlipFilter;
+use crate::catalog::{CatalogFilter, ItemCatalog};
+use crate::types::{Coins, ItemId};
+use crate::watch::PriceSide;
+use crate::ApiEndpoint;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+use toml::Spanned;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A watchlist with alert rules and flip filters, loaded from TOML.
+///
+/// Items can be referred to by id or by name. Durations are written as a number of seconds or
+/// with a unit (`"90s"`, `"5m"`, `"1h"`, `"1d"`), and prices as a number of coins or in GE
+/// shorthand (`"1.5m"`):
+///
+/// ```toml
+/// endpoint = "osrs"
+/// interval = "30s"
+/// items = [4151, "Dragon bones"]
+///
+/// [[alerts]]
+/// name = "cheap whip"
+/// item = "Abyssal whip"
+/// cooldown = "1h"
+/// condition = { type = "price_below", side = "low", price = "1.5m" }
+///
+/// [flips]
+/// members = false
+/// min_volume = 1000
+/// ```
+///
+/// Errors point at the line and column of the offending value, including names that don't match
+/// an item, which are checked when resolving the config against an [`ItemCatalog`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default = "default_endpoint")]
+    pub endpoint: ApiEndpoint,
+    /// How often to poll for prices.
+    #[serde(default = "default_interval", deserialize_with = "deserialize_duration")]
+    pub interval: Duration,
+    /// Items to watch.
+    #[serde(default)]
+    pub items: Vec<Spanned<ItemRef>>,
+    #[serde(default)]
+    pub alerts: Vec<AlertConfig>,
+    #[serde(default)]
+    pub flips: FlipsConfig,
+    /// The TOML the config was parsed from, to locate errors found while resolving items.
+    #[serde(skip)]
+    source: String,
+}
+
+/// An item referred to by id or by name, matched ignoring case.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ItemRef {
+    Id(u32),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertConfig {
+    pub name: Spanned<String>,
+    pub item: Spanned<ItemRef>,
+    #[serde(default, deserialize_with = "deserialize_duration")]
+    pub cooldown: Duration,
+    pub condition: ConditionConfig,
+}
+
+/// The TOML form of an alert [`Condition`], tagged by `type`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ConditionConfig {
+    PriceBelow {
+        side: PriceSide,
+        #[serde(deserialize_with = "deserialize_coins")]
+        price: Coins,
+    },
+    PriceAbove {
+        side: PriceSide,
+        #[serde(deserialize_with = "deserialize_coins")]
+        price: Coins,
+    },
+    PercentMove {
+        side: PriceSide,
+        #[serde(deserialize_with = "deserialize_positive")]
+        percent: f64,
+        #[serde(deserialize_with = "deserialize_duration")]
+        window: Duration,
+    },
+    MarginAbove {
+        #[serde(deserialize_with = "deserialize_coins")]
+        margin: Coins,
+    },
+    VolumeSpike {
+        #[serde(deserialize_with = "deserialize_positive")]
+        factor: f64,
+        #[serde(default)]
+        min_volume: u64,
+        #[serde(default = "default_baseline")]
+        baseline: usize,
+    },
+}
+
+/// The TOML form of a [`FlipFilter`].
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FlipsConfig {
+    pub members: Option<bool>,
+    pub min_volume: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_optional_duration")]
+    pub max_price_age: Option<Duration>,
+    pub min_roi: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    /// The line number, starting at 1.
+    pub line: usize,
+    /// The column number in characters, starting at 1.
+    pub column: usize,
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{}{message}", location.map(|location| format!("{}: ", location)).unwrap_or_default())]
+    Invalid { message: String, location: Option<Location> },
+}
+
+impl ConfigError {
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            ConfigError::Io(_) => None,
+            ConfigError::Invalid { location, .. } => *location,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        let mut config: Config = toml::from_str(source).map_err(|error| ConfigError::Invalid {
+            message: error.message().to_string(),
+            location: error.span().map(|span| location(source, span.start)),
+        })?;
+        config.source = source.to_string();
+
+        let mut names = std::collections::HashSet::new();
+        for alert in &config.alerts {
+            if !names.insert(alert.name.get_ref()) {
+                return Err(config.invalid(format!("duplicate alert name {:?}", alert.name.get_ref()), alert.name.span()));
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Resolves the watched items against the catalog.
+    pub fn watched_items(&self, catalog: &ItemCatalog) -> Result<Vec<ItemId>, ConfigError> {
+        self.items.iter().map(|item| self.resolve(item, catalog)).collect()
+    }
+
+    /// Resolves the alerts against the catalog into rules for an
+    /// [`AlertEngine`](crate::alerts::AlertEngine).
+    pub fn alert_rules(&self, catalog: &ItemCatalog) -> Result<Vec<AlertRule>, ConfigError> {
+        self.alerts
+            .iter()
+            .map(|alert| {
+                let item_id = self.resolve(&alert.item, catalog)?;
+                Ok(AlertRule::new(alert.name.get_ref(), item_id, alert.condition.to_condition()).cooldown(alert.cooldown))
+            })
+            .collect()
+    }
+
+    pub fn flip_filter(&self) -> FlipFilter {
+        FlipFilter {
+            members: self.flips.members,
+            min_volume: self.flips.min_volume,
+            max_price_age: self.flips.max_price_age.and_then(|age| chrono::Duration::from_std(age).ok()),
+            min_roi: self.flips.min_roi,
+        }
+    }
+
+    fn resolve(&self, item: &Spanned<ItemRef>, catalog: &ItemCatalog) -> Result<ItemId, ConfigError> {
+        let found = match item.get_ref() {
+            ItemRef::Id(id) => catalog.get(ItemId::new(*id)),
+            ItemRef::Name(name) => catalog.by_name_ignore_case(name),
+        };
+        if let Some(found) = found {
+            return Ok(found.id);
+        }
+        let message = match item.get_ref() {
+            ItemRef::Id(id) => format!("unknown item id {}", id),
+            ItemRef::Name(name) => match catalog.search(name, &CatalogFilter::default(), 1).first() {
+                Some(suggestion) => format!("unknown item {:?}, did you mean {:?}?", name, suggestion.item.name),
+                None => format!("unknown item {:?}", name),
+            },
+        };
+        Err(self.invalid(message, item.span()))
+    }
+
+    fn invalid(&self, message: String, span: Range<usize>) -> ConfigError {
+        ConfigError::Invalid { message, location: Some(location(&self.source, span.start)) }
+    }
+}
+
+impl ConditionConfig {
+    pub fn to_condition(&self) -> Condition {
+        match *self {
+            ConditionConfig::PriceBelow { side, price } => Condition::PriceBelow { side, price },
+            ConditionConfig::PriceAbove { side, price } => Condition::PriceAbove { side, price },
+            ConditionConfig::PercentMove { side, percent, window } => Condition::PercentMove { side, percent, window },
+            ConditionConfig::MarginAbove { margin } => Condition::MarginAbove { margin },
+            ConditionConfig::VolumeSpike { factor, min_volume, baseline } => {
+                Condition::VolumeSpike { factor, min_volume, baseline }
+            }
+        }
+    }
+}
+
+fn default_endpoint() -> ApiEndpoint {
+    ApiEndpoint::OldSchoolRuneScape
+}
+
+fn default_interval() -> Duration {
+    DEFAULT_INTERVAL
+}
+
+fn default_baseline() -> usize {
+    12
+}
+
+fn location(source: &str, offset: usize) -> Location {
+    let before = source.get(..offset).unwrap_or(source);
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Location { line: before.matches('\n').count() + 1, column: before[line_start..].chars().count() + 1 }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(u64),
+    String(String),
+}
+
+/// Parses `"90s"`, `"5m"`, `"1h"` or `"1d"`, or a plain number of seconds.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+    let unit_seconds = match unit.trim() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.checked_mul(unit_seconds)?))
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(seconds) => Ok(Duration::from_secs(seconds)),
+        NumberOrString::String(value) => parse_duration(&value)
+            .ok_or_else(|| D::Error::custom(format!("invalid duration {:?}, expected e.g. \"90s\", \"5m\" or \"1h\"", value))),
+    }
+}
+
+fn deserialize_optional_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    deserialize_duration(deserializer).map(Some)
+}
+
+fn deserialize_coins<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Coins, D::Error> {
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(amount) => u32::try_from(amount)
+            .map(Coins::new)
+            .map_err(|_| D::Error::custom(format!("{} coins is more than the maximum of {:#}", amount, Coins::MAX))),
+        NumberOrString::String(value) => value.parse().map_err(|error| D::Error::custom(format!("invalid price {:?}: {}", value, error))),
+    }
+}
+
+fn deserialize_positive<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let value = f64::deserialize(deserializer)?;
+    if value > 0.0 && value.is_finite() {
+        Ok(value)
+    } else {
+        Err(D::Error::custom(format!("expected a positive number, found {}", value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alerts::{AlertRule, Condition};
+    use crate::catalog::ItemCatalog;
+    use crate::config::{Config, ConfigError, Location};
+    use crate::endpoints::mapping::MappingItem;
+    use crate::types::{Coins, ItemId};
+    use crate::watch::PriceSide;
+    use crate::ApiEndpoint;
+    use std::time::Duration;
+
+    const CONFIG: &str = r#"
+endpoint = "dmm"
+interval = "30s"
+items = [4151, "zulrah's scales"]
+
+[[alerts]]
+name = "cheap whip"
+item = "Abyssal whip"
+cooldown = "1h"
+condition = { type = "price_below", side = "low", price = "1.5m" }
+
+[[alerts]]
+name = "nature runes moving"
+item = 561
+condition = { type = "percent_move", side = "high", percent = 5, window = "10m" }
+
+[[alerts]]
+name = "scales volume"
+item = 12934
+condition = { type = "volume_spike", factor = 3.0, min_volume = 10000 }
+
+[flips]
+members = false
+min_volume = 1000
+max_price_age = "30m"
+min_roi = 0.01
+"#;
+
+    fn item(id: u32, name: &str) -> MappingItem {
+        MappingItem {
+            examine: format!("{}.", name),
+            id: ItemId::new(id),
+            members: true,
+            low_alch: None,
+            limit: None,
+            value: None,
+            high_alch: None,
+            icon: format!("{}.png", name),
+            name: name.to_string(),
+        }
+    }
+
+    fn catalog() -> ItemCatalog {
+        ItemCatalog::new(vec![
+            item(2, "Cannonball"),
+            item(554, "Fire rune"),
+            item(561, "Nature rune"),
+            item(4151, "Abyssal whip"),
+            item(12934, "Zulrah's scales"),
+        ])
+    }
+
+    fn invalid(source: &str) -> (String, Location) {
+        let error = Config::from_toml(source).unwrap_err();
+        let location = error.location().unwrap();
+        let ConfigError::Invalid { message, .. } = error else { unreachable!() };
+        (message, location)
+    }
+
+    #[test]
+    fn test_parse() {
+        let catalog = catalog();
+        let config = Config::from_toml(CONFIG).unwrap();
+        assert_eq!(config.endpoint, ApiEndpoint::DeadmanArmageddon);
+        assert_eq!(config.interval, Duration::from_secs(30));
+        assert_eq!(config.watched_items(&catalog).unwrap(), vec![ItemId::new(4151), ItemId::new(12934)]);
+
+        let rules = config.alert_rules(&catalog).unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                AlertRule::new("cheap whip", ItemId::new(4151), Condition::PriceBelow { side: PriceSide::Low, price: Coins::new(1_500_000) })
+                    .cooldown(Duration::from_secs(3600)),
+                AlertRule::new(
+                    "nature runes moving",
+                    ItemId::new(561),
+                    Condition::PercentMove { side: PriceSide::High, percent: 5.0, window: Duration::from_secs(600) },
+                ),
+                AlertRule::new("scales volume", ItemId::new(12934), Condition::VolumeSpike { factor: 3.0, min_volume: 10000, baseline: 12 }),
+            ]
+        );
+
+        let filter = config.flip_filter();
+        assert_eq!(filter.members, Some(false));
+        assert_eq!(filter.min_volume, Some(1000));
+        assert_eq!(filter.max_price_age, Some(chrono::Duration::minutes(30)));
+        assert_eq!(filter.min_roi, Some(0.01));
+
+        let config = Config::from_toml("").unwrap();
+        assert_eq!(config.endpoint, ApiEndpoint::OldSchoolRuneScape);
+        assert_eq!(config.interval, Duration::from_secs(60));
+        assert!(config.alerts.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let (message, location) = invalid("endpoint = \"rs3\"\n");
+        assert!(message.contains("unknown variant `rs3`"), "{}", message);
+        assert_eq!(location, Location { line: 1, column: 12 });
+
+        let (message, location) = invalid("interval = \"soon\"\n");
+        assert!(message.contains("invalid duration \"soon\""), "{}", message);
+        assert_eq!(location.line, 1);
+
+        let source = "[[alerts]]\nname = \"a\"\nitem = 4151\ncondition = { type = \"price_below\", side = \"low\", price = \"1.5q\" }\n";
+        let (message, location) = invalid(source);
+        assert!(message.contains("invalid price \"1.5q\""), "{}", message);
+        assert_eq!(location.line, 4);
+
+        let source = "[[alerts]]\nname = \"a\"\nitem = 4151\ncondition = { type = \"percent_move\", side = \"high\", percent = -5, window = 600 }\n";
+        let (message, location) = invalid(source);
+        assert!(message.contains("expected a positive number"), "{}", message);
+        assert_eq!(location.line, 4);
+
+        let (message, location) = invalid("\n[flips]\nmin_roi = 0.1\nmax_margin = 5\n");
+        assert!(message.contains("unknown field `max_margin`"), "{}", message);
+        assert_eq!(location, Location { line: 4, column: 1 });
+
+        let source = "[[alerts]]\nname = \"a\"\nitem = 1\ncondition = { type = \"margin_above\", margin = 1 }\n\n[[alerts]]\nname = \"a\"\nitem = 2\ncondition = { type = \"margin_above\", margin = 1 }\n";
+        let (message, location) = invalid(source);
+        assert_eq!(message, "duplicate alert name \"a\"");
+        assert_eq!(location, Location { line: 7, column: 8 });
+    }
+
+    #[test]
+    fn test_unknown_items() {
+        let catalog = catalog();
+        let config = Config::from_toml("items = [4151, 1]\n").unwrap();
+        let error = config.watched_items(&catalog).unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 16: unknown item id 1");
+
+        let source = "\n[[alerts]]\nname = \"whip\"\nitem = \"Abby whip\"\ncondition = { type = \"price_above\", side = \"high\", price = 2000000 }\n";
+        let config = Config::from_toml(source).unwrap();
+        let error = config.alert_rules(&catalog).unwrap_err();
+        assert_eq!(error.to_string(), "line 4, column 8: unknown item \"Abby whip\", did you mean \"Abyssal whip\"?");
+    }
+}
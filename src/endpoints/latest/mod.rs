@@ -1,68 +1,53 @@
-use crate::types::ItemId;
-use crate::Client;
+use crate::cache::CachedEndpoint;
+use crate::types::{Coins, ItemId};
+use crate::{Client, Error, Transport};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LatestResponse {
     pub data: HashMap<ItemId, LatestItem>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LatestItem {
-    pub high: Option<u32>,
-    pub low: Option<u32>,
+    pub high: Option<Coins>,
+    pub low: Option<Coins>,
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub high_time: Option<DateTime<Utc>>,
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub low_time: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum LatestError {
-    #[error(transparent)]
-    ReqwestError(#[from] reqwest::Error),
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum LatestByIdError {
-    #[error(transparent)]
-    ReqwestError(#[from] reqwest::Error),
-    #[error("Item not found")]
-    ItemNotFound,
-}
-
 pub trait LatestEndpoint {
-    fn latest(&self) -> impl Future<Output=Result<HashMap<ItemId, LatestItem>, LatestError>>;
-    fn latest_by_id(&self, id: ItemId) -> impl Future<Output=Result<LatestItem, LatestByIdError>>;
+    fn latest(&self) -> impl Future<Output=Result<HashMap<ItemId, LatestItem>, Error>>;
+    fn latest_by_id(&self, id: ItemId) -> impl Future<Output=Result<LatestItem, Error>>;
 }
 
-impl LatestEndpoint for Client {
-    async fn latest(&self) -> Result<HashMap<ItemId, LatestItem>, LatestError> {
+impl<T: Transport> LatestEndpoint for Client<T> {
+    async fn latest(&self) -> Result<HashMap<ItemId, LatestItem>, Error> {
         let url = format!("{}/latest", self.base_url);
-        let response = self.http_client.get(url).send().await?;
-        let result: LatestResponse = response.json().await?;
+        let result: LatestResponse = self.get_json_cached(CachedEndpoint::Latest, &url).await?;
         Ok(result.data)
     }
 
-    async fn latest_by_id(&self, id: ItemId) -> Result<LatestItem, LatestByIdError> {
+    async fn latest_by_id(&self, id: ItemId) -> Result<LatestItem, Error> {
         let url = format!("{}/latest?id={}", self.base_url, id.id());
-        let response = self.http_client.get(url).send().await?;
-        let mut result: LatestResponse = response.json().await?;
+        let mut result: LatestResponse = self.get_json(&url).await?;
         if let Some(item) = result.data.remove(&id) {
             Ok(item)
         } else {
-            Err(LatestByIdError::ItemNotFound)
+            Err(Error::ItemNotFound)
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::endpoints::latest::LatestEndpoint;
-    use crate::testing::get_test_client;
+    use crate::endpoints::latest::{LatestEndpoint, LatestResponse};
+    use crate::testing::{assert_round_trip, get_fixture_body, get_test_client};
     use crate::types::ItemId;
     use crate::ApiEndpoint;
 
@@ -83,10 +68,10 @@ mod tests {
                 assert!(low_time.timestamp() > 0);
             }
             if let Some(high) = item.high {
-                assert!(high > 0);
+                assert!(high.amount() > 0);
             }
             if let Some(low) = item.low {
-                assert!(low > 0);
+                assert!(low.amount() > 0);
             }
         }
     }
@@ -106,10 +91,20 @@ mod tests {
             assert!(low_time.timestamp() > 0);
         }
         if let Some(high) = item.high {
-            assert!(high > 0);
+            assert!(high.amount() > 0);
         }
         if let Some(low) = item.low {
-            assert!(low > 0);
+            assert!(low.amount() > 0);
         }
     }
+
+    #[test]
+    fn test_round_trip() {
+        assert_round_trip::<LatestResponse>(get_fixture_body(ApiEndpoint::OldSchoolRuneScape, "/latest"));
+        assert_round_trip::<LatestResponse>(get_fixture_body(ApiEndpoint::OldSchoolRuneScape, "/latest?id=4151"));
+        let response = assert_round_trip::<LatestResponse>(serde_json::json!({
+            "data": { "2": { "high": null, "highTime": null, "low": 190, "lowTime": 1760702363 } },
+        }));
+        assert_eq!(response.data[&ItemId::new(2)].high, None);
+    }
 }
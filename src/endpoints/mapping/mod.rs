@@ -1,46 +1,48 @@
-use crate::types::ItemId;
-use crate::Client;
+use crate::cache::CachedEndpoint;
+use crate::types::{Coins, ItemId};
+use crate::{Client, Error, Transport};
 
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MappingItem {
     pub examine: String,
     pub id: ItemId,
     pub members: bool,
-    #[serde(rename = "lowalch")]
-    pub low_alch: Option<u32>,
+    #[serde(rename = "lowalch", skip_serializing_if = "Option::is_none")]
+    pub low_alch: Option<Coins>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
-    pub value: Option<u32>,
-    #[serde(rename = "highalch")]
-    pub high_alch: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Coins>,
+    #[serde(rename = "highalch", skip_serializing_if = "Option::is_none")]
+    pub high_alch: Option<Coins>,
     pub icon: String,
     pub name: String,
 }
 
-#[derive(Debug, thiserror::Error)]
-pub enum MappingError {
-    #[error(transparent)]
-    ReqwestError(#[from] reqwest::Error),
+impl MappingItem {
+    /// Returns the URL of the item's inventory icon on the wiki.
+    pub fn icon_url(&self) -> String {
+        crate::icons::wiki_image_url(&self.icon)
+    }
 }
 
 pub trait MappingEndpoint {
-    fn mapping(&self) -> impl Future<Output=Result<Vec<MappingItem>, MappingError>>;
+    fn mapping(&self) -> impl Future<Output=Result<Vec<MappingItem>, Error>>;
 }
 
-impl MappingEndpoint for Client {
-    async fn mapping(&self) -> Result<Vec<MappingItem>, MappingError> {
+impl<T: Transport> MappingEndpoint for Client<T> {
+    async fn mapping(&self) -> Result<Vec<MappingItem>, Error> {
         let url = format!("{}/mapping", self.base_url);
-        let response = self.http_client.get(url).send().await?;
-        let result = response.json().await?;
-        Ok(result)
+        self.get_json_cached(CachedEndpoint::Mapping, &url).await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::endpoints::mapping::MappingEndpoint;
-    use crate::testing::get_test_client;
+    use crate::endpoints::mapping::{MappingEndpoint, MappingItem};
+    use crate::testing::{assert_round_trip, get_fixture_body, get_test_client};
     use crate::ApiEndpoint;
 
     #[tokio::test]
@@ -57,4 +59,19 @@ mod tests {
             assert!(!item.icon.is_empty());
         }
     }
+
+    #[test]
+    fn test_round_trip() {
+        assert_round_trip::<Vec<MappingItem>>(get_fixture_body(ApiEndpoint::OldSchoolRuneScape, "/mapping"));
+        let item = assert_round_trip::<MappingItem>(serde_json::json!({
+            "examine": "A worn-out bond.",
+            "id": 13191,
+            "members": false,
+            "value": 0,
+            "icon": "Old school bond (untradeable).png",
+            "name": "Old school bond (untradeable)",
+        }));
+        assert_eq!(item.limit, None);
+        assert_eq!(item.high_alch, None);
+    }
 }
@@ -1,20 +1,22 @@
-use crate::types::ItemId;
+use crate::endpoints::timeseries::Timestep;
+use crate::types::{Coins, ItemId};
+use crate::{Client, Error, Transport};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
 pub mod five_minutes;
 pub mod one_hour;
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PriceItem {
-    pub avg_high_price: Option<u32>,
-    pub avg_low_price: Option<u32>,
+    pub avg_high_price: Option<Coins>,
+    pub avg_low_price: Option<Coins>,
     pub high_price_volume: u32,
     pub low_price_volume: u32,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PricesResponseSuccess {
     pub data: HashMap<ItemId, PriceItem>,
@@ -22,22 +24,50 @@ pub struct PricesResponseSuccess {
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PricesResponseError {
     pub error: String,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
 pub enum PricesResponse {
     Success(PricesResponseSuccess),
     Error(PricesResponseError),
 }
 
+pub trait PricesEndpoint {
+    /// Fetches the average prices of all items over the given interval.
+    ///
+    /// Without a timestamp the most recent interval is returned. A timestamp marks the start of
+    /// the interval and must be a multiple of the interval length, e.g. 300 seconds for
+    /// [`Timestep::FiveMinutes`].
+    fn prices(&self, interval: Timestep, timestamp: Option<DateTime<Utc>>) -> impl Future<Output=Result<PricesResponseSuccess, Error>>;
+}
+
+impl<T: Transport> PricesEndpoint for Client<T> {
+    async fn prices(&self, interval: Timestep, timestamp: Option<DateTime<Utc>>) -> Result<PricesResponseSuccess, Error> {
+        let url = match timestamp {
+            Some(timestamp) => {
+                if !interval.is_aligned(timestamp) {
+                    return Err(Error::UnalignedTimestamp { interval, timestamp });
+                }
+                format!("{}/{}?timestamp={}", self.base_url, interval.as_ref(), timestamp.timestamp())
+            }
+            None => format!("{}/{}", self.base_url, interval.as_ref()),
+        };
+        self.get_json(&url).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::endpoints::prices::{PricesResponse, PricesResponseSuccess};
+    use crate::endpoints::prices::{PricesEndpoint, PricesResponse, PricesResponseSuccess};
+    use crate::endpoints::timeseries::Timestep;
+    use crate::testing::{assert_round_trip, get_fixture_body, get_test_client};
+    use crate::{ApiEndpoint, Error};
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn parse_test_success() {
@@ -70,4 +100,44 @@ mod tests {
             _ => panic!("Expected an error response"),
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_prices_intervals() {
+        let client = get_test_client(ApiEndpoint::OldSchoolRuneScape);
+        for interval in [Timestep::FiveMinutes, Timestep::OneHour, Timestep::SixHours, Timestep::OneDay] {
+            let result = client.prices(interval, None).await;
+            assert!(result.is_ok());
+            let prices = result.unwrap();
+            assert!(interval.is_aligned(prices.timestamp));
+            assert!(!prices.data.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prices_unaligned_timestamp() {
+        let client = get_test_client(ApiEndpoint::OldSchoolRuneScape);
+        let date_time = Utc.timestamp_opt(1697059500, 0).single().expect("Invalid timestamp");
+        let result = client.prices(Timestep::OneHour, Some(date_time)).await;
+        match result {
+            Err(Error::UnalignedTimestamp { interval, timestamp }) => {
+                assert_eq!(interval, Timestep::OneHour);
+                assert_eq!(timestamp, date_time);
+            }
+            _ => panic!("Expected an unaligned timestamp error"),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for path in ["/5m", "/5m?timestamp=1760701800", "/1h", "/6h", "/24h"] {
+            assert_round_trip::<PricesResponseSuccess>(get_fixture_body(ApiEndpoint::OldSchoolRuneScape, path));
+        }
+        let response = assert_round_trip::<PricesResponse>(serde_json::json!({
+            "data": { "2": { "avgHighPrice": null, "highPriceVolume": 0, "avgLowPrice": 190, "lowPriceVolume": 12 } },
+            "timestamp": 1760701800,
+        }));
+        assert!(matches!(response, PricesResponse::Success(_)));
+        let response = assert_round_trip::<PricesResponse>(serde_json::json!({ "error": "Invalid timestamp" }));
+        assert!(matches!(response, PricesResponse::Error(_)));
+    }
+}
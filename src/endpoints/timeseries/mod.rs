@@ -1,15 +1,40 @@
-use crate::types::ItemId;
-use crate::Client;
+use crate::types::{Coins, ItemId};
+use crate::{Client, Error, Transport};
 use chrono::{DateTime, Utc};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Timestep {
+    #[serde(rename = "5m")]
     FiveMinutes,
+    #[serde(rename = "1h")]
     OneHour,
+    #[serde(rename = "6h")]
     SixHours,
+    #[serde(rename = "24h")]
     OneDay,
 }
 
+impl Timestep {
+    pub fn seconds(&self) -> i64 {
+        match self {
+            Timestep::FiveMinutes => 5 * 60,
+            Timestep::OneHour => 60 * 60,
+            Timestep::SixHours => 6 * 60 * 60,
+            Timestep::OneDay => 24 * 60 * 60,
+        }
+    }
+
+    pub fn is_aligned(&self, timestamp: DateTime<Utc>) -> bool {
+        timestamp.timestamp_subsec_nanos() == 0 && timestamp.timestamp() % self.seconds() == 0
+    }
+
+    /// Rounds a timestamp down to the start of the interval containing it.
+    pub fn align(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = timestamp.timestamp().div_euclid(self.seconds()) * self.seconds();
+        DateTime::from_timestamp(seconds, 0).unwrap_or(timestamp)
+    }
+}
+
 impl AsRef<str> for Timestep {
     fn as_ref(&self) -> &str {
         match self {
@@ -21,47 +46,41 @@ impl AsRef<str> for Timestep {
     }
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TimeseriesItem {
-    pub avg_high_price: Option<u32>,
-    pub avg_low_price: Option<u32>,
+    pub avg_high_price: Option<Coins>,
+    pub avg_low_price: Option<Coins>,
     pub high_price_volume: u32,
     pub low_price_volume: u32,
     #[serde(with = "chrono::serde::ts_seconds")]
     pub timestamp: DateTime<Utc>,
 }
 
-
-#[derive(Debug, Clone, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TimeseriesResponse {
     pub data: Vec<TimeseriesItem>,
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum TimeseriesError {
-    #[error(transparent)]
-    ReqwestError(#[from] reqwest::Error),
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<ItemId>,
 }
 
 pub trait TimeseriesEndpoint {
-    fn timeseries(&self, id: ItemId, timestep: Timestep) -> impl Future<Output=Result<Vec<TimeseriesItem>, TimeseriesError>>;
+    fn timeseries(&self, id: ItemId, timestep: Timestep) -> impl Future<Output=Result<Vec<TimeseriesItem>, Error>>;
 }
 
-impl TimeseriesEndpoint for Client {
-    async fn timeseries(&self, id: ItemId, timestep: Timestep) -> Result<Vec<TimeseriesItem>, TimeseriesError> {
+impl<T: Transport> TimeseriesEndpoint for Client<T> {
+    async fn timeseries(&self, id: ItemId, timestep: Timestep) -> Result<Vec<TimeseriesItem>, Error> {
         let url = format!("{}/timeseries?timestep={}&id={}", self.base_url, timestep.as_ref(), id.id());
-        let response = self.http_client.get(url).send().await?;
-        let result: TimeseriesResponse = response.json().await?;
+        let result: TimeseriesResponse = self.get_json(&url).await?;
         Ok(result.data)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::endpoints::timeseries::{TimeseriesEndpoint, Timestep};
-    use crate::testing::get_test_client;
+    use crate::endpoints::timeseries::{TimeseriesEndpoint, TimeseriesResponse, Timestep};
+    use crate::testing::{assert_round_trip, get_fixture_body, get_test_client};
     use crate::types::ItemId;
     use crate::ApiEndpoint;
 
@@ -76,4 +95,35 @@ mod tests {
             assert!(item.timestamp.timestamp() > 0);
         }
     }
+
+    #[test]
+    fn test_timestep_alignment() {
+        use chrono::{TimeZone, Utc};
+
+        let date_time = Utc.timestamp_opt(1697090400, 0).unwrap();
+        assert!(Timestep::FiveMinutes.is_aligned(date_time));
+        assert!(Timestep::OneHour.is_aligned(date_time));
+        assert!(Timestep::SixHours.is_aligned(date_time));
+        assert!(!Timestep::OneDay.is_aligned(date_time));
+        assert!(!Timestep::FiveMinutes.is_aligned(date_time + chrono::Duration::seconds(60)));
+
+        assert_eq!(Timestep::FiveMinutes.align(date_time + chrono::Duration::seconds(299)), date_time);
+        assert_eq!(Timestep::OneDay.align(date_time), Utc.timestamp_opt(1697068800, 0).unwrap());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = "/timeseries?timestep=5m&id=4151";
+        assert_round_trip::<TimeseriesResponse>(get_fixture_body(ApiEndpoint::OldSchoolRuneScape, path));
+        let response = assert_round_trip::<TimeseriesResponse>(serde_json::json!({
+            "data": [{ "timestamp": 1760698800, "avgHighPrice": 1500000, "avgLowPrice": null, "highPriceVolume": 5, "lowPriceVolume": 0 }],
+            "itemId": 4151,
+        }));
+        assert_eq!(response.item_id, Some(ItemId::new(4151)));
+
+        for (timestep, json) in [(Timestep::FiveMinutes, "\"5m\""), (Timestep::OneHour, "\"1h\""), (Timestep::SixHours, "\"6h\""), (Timestep::OneDay, "\"24h\"")] {
+            assert_eq!(serde_json::to_string(&timestep).unwrap(), json);
+            assert_eq!(serde_json::from_str::<Timestep>(json).unwrap(), timestep);
+        }
+    }
 }
@@ -0,0 +1,66 @@
+use crate::endpoints::timeseries::Timestep;
+use crate::transport::TransportError;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    #[error("Unexpected HTTP status {status}: {body}")]
+    Status { status: StatusCode, body: String },
+    #[error("Rate limited by the API")]
+    RateLimited { retry_after: Option<Duration> },
+    /// An `{"error": ...}` body, with the HTTP status when the response was not successful.
+    #[error("API error: {message}")]
+    Api { message: String, status: Option<StatusCode> },
+    #[error("Failed to decode response at `{path}`: {source}")]
+    Decode {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Item not found")]
+    ItemNotFound,
+    #[error("Request failed after {attempts} attempts: {source}")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<Error>,
+    },
+    #[error("Timestamp {timestamp} is not aligned to the {} interval", interval.as_ref())]
+    UnalignedTimestamp {
+        interval: Timestep,
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl Error {
+    /// Returns the HTTP status code of the response that caused this error, if any.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Status { status, .. } => Some(*status),
+            Error::Api { status, .. } => *status,
+            Error::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            Error::RetriesExhausted { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// Returns how many attempts were made before this error was returned.
+    pub fn attempts(&self) -> u32 {
+        match self {
+            Error::RetriesExhausted { attempts, .. } => *attempts,
+            _ => 1,
+        }
+    }
+
+    /// Returns the error of the last attempt, unwrapping [`Error::RetriesExhausted`].
+    pub fn last_error(&self) -> &Error {
+        match self {
+            Error::RetriesExhausted { source, .. } => source,
+            error => error,
+        }
+    }
+}
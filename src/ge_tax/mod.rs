@@ -0,0 +1,158 @@
+use crate::endpoints::timeseries::TimeseriesItem;
+use crate::types::{Coins, ItemId};
+use chrono::{DateTime, Utc};
+
+/// The Grand Exchange tax rule in effect from a given date.
+///
+/// Sellers pay `rate` of the sale price per item, rounded down and capped at `cap`. Items sold for
+/// less than `min_taxed_price` and the items in `exempt_items` are not taxed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxRule {
+    effective_from: i64,
+    pub rate_basis_points: u32,
+    pub cap: Coins,
+    pub min_taxed_price: Coins,
+    pub exempt_items: &'static [ItemId],
+}
+
+const EXEMPT_ITEMS: &[ItemId] = &[
+    ItemId::new(13190), // Old school bond
+    ItemId::new(1755),  // Chisel
+    ItemId::new(5325),  // Gardening trowel
+    ItemId::new(1785),  // Glassblowing pipe
+    ItemId::new(2347),  // Hammer
+    ItemId::new(1733),  // Needle
+    ItemId::new(233),   // Pestle and mortar
+    ItemId::new(5341),  // Rake
+    ItemId::new(8794),  // Saw
+    ItemId::new(5329),  // Secateurs
+    ItemId::new(5343),  // Seed dibber
+    ItemId::new(1735),  // Shears
+    ItemId::new(952),   // Spade
+    ItemId::new(5331),  // Watering can
+];
+
+/// Every tax rule so far, ordered by the date it took effect. Sales before the first rule were untaxed.
+pub const RULES: &[TaxRule] = &[
+    // 9 December 2021: 1% tax introduced alongside the item sink.
+    TaxRule {
+        effective_from: 1_639_008_000,
+        rate_basis_points: 100,
+        cap: Coins::new(5_000_000),
+        min_taxed_price: Coins::new(100),
+        exempt_items: EXEMPT_ITEMS,
+    },
+    // 29 May 2025: tax raised to 2%.
+    TaxRule {
+        effective_from: 1_748_476_800,
+        rate_basis_points: 200,
+        cap: Coins::new(5_000_000),
+        min_taxed_price: Coins::new(50),
+        exempt_items: EXEMPT_ITEMS,
+    },
+];
+
+impl TaxRule {
+    pub fn effective_from(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.effective_from, 0).expect("Invalid rule timestamp")
+    }
+
+    pub fn is_exempt(&self, item: ItemId) -> bool {
+        self.exempt_items.contains(&item)
+    }
+
+    /// Returns the tax paid on selling one `item` for `price`.
+    pub fn tax(&self, item: ItemId, price: Coins) -> Coins {
+        if self.is_exempt(item) || price < self.min_taxed_price {
+            return Coins::ZERO;
+        }
+        let tax = price.widening_mul(self.rate_basis_points) / 10_000;
+        Coins::new(u32::try_from(tax).unwrap_or(u32::MAX)).min(self.cap)
+    }
+
+    /// Returns what the seller receives for one `item` sold for `price`.
+    pub fn post_tax(&self, item: ItemId, price: Coins) -> Coins {
+        price.saturating_sub(self.tax(item, price))
+    }
+}
+
+/// Returns the rule that applied at `timestamp`, or `None` before the tax was introduced.
+pub fn rule_at(timestamp: DateTime<Utc>) -> Option<&'static TaxRule> {
+    RULES.iter().rev().find(|rule| rule.effective_from <= timestamp.timestamp())
+}
+
+pub fn current_rule() -> &'static TaxRule {
+    RULES.last().expect("No tax rules defined")
+}
+
+/// Returns the tax paid on selling one `item` for `price` at `timestamp`.
+pub fn tax(item: ItemId, price: Coins, timestamp: DateTime<Utc>) -> Coins {
+    rule_at(timestamp).map_or(Coins::ZERO, |rule| rule.tax(item, price))
+}
+
+/// Returns what the seller received for one `item` sold for `price` at `timestamp`.
+pub fn post_tax(item: ItemId, price: Coins, timestamp: DateTime<Utc>) -> Coins {
+    price.saturating_sub(tax(item, price, timestamp))
+}
+
+/// Returns the post-tax proceeds of selling `item` at the average high price of a timeseries
+/// entry, using the rule in effect at the entry's timestamp.
+pub fn timeseries_post_tax(item: ItemId, entry: &TimeseriesItem) -> Option<Coins> {
+    entry.avg_high_price.map(|price| post_tax(item, price, entry.timestamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::timeseries::TimeseriesItem;
+    use crate::ge_tax::{current_rule, post_tax, rule_at, tax, timeseries_post_tax, RULES};
+    use crate::types::{Coins, ItemId};
+    use chrono::{TimeZone, Utc};
+
+    const WHIP: ItemId = ItemId::new(4151);
+    const BOND: ItemId = ItemId::new(13190);
+
+    #[test]
+    fn test_rules_ordered() {
+        assert!(RULES.windows(2).all(|rules| rules[0].effective_from < rules[1].effective_from));
+        assert_eq!(RULES[0].effective_from(), Utc.with_ymd_and_hms(2021, 12, 9, 0, 0, 0).unwrap());
+        assert_eq!(RULES[1].effective_from(), Utc.with_ymd_and_hms(2025, 5, 29, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_current_rule() {
+        let rule = current_rule();
+        assert_eq!(rule.tax(WHIP, Coins::new(1_500_000)), Coins::new(30_000));
+        assert_eq!(rule.post_tax(WHIP, Coins::new(1_500_000)), Coins::new(1_470_000));
+        assert_eq!(rule.tax(WHIP, Coins::new(49)), Coins::ZERO);
+        assert_eq!(rule.tax(WHIP, Coins::new(50)), Coins::new(1));
+        assert_eq!(rule.tax(WHIP, Coins::new(2_147_483_647)), Coins::new(5_000_000));
+        assert_eq!(rule.tax(BOND, Coins::new(7_000_000)), Coins::ZERO);
+    }
+
+    #[test]
+    fn test_rule_by_date() {
+        let before_tax = Utc.with_ymd_and_hms(2021, 6, 1, 0, 0, 0).unwrap();
+        let one_percent = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+        let two_percent = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        assert!(rule_at(before_tax).is_none());
+        assert_eq!(tax(WHIP, Coins::new(1_500_000), before_tax), Coins::ZERO);
+        assert_eq!(tax(WHIP, Coins::new(1_500_000), one_percent), Coins::new(15_000));
+        assert_eq!(tax(WHIP, Coins::new(99), one_percent), Coins::ZERO);
+        assert_eq!(tax(WHIP, Coins::new(1_500_000), two_percent), Coins::new(30_000));
+        assert_eq!(post_tax(WHIP, Coins::new(99), two_percent), Coins::new(98));
+    }
+
+    #[test]
+    fn test_timeseries_post_tax() {
+        let entry = TimeseriesItem {
+            avg_high_price: Some(Coins::new(1_000_000)),
+            avg_low_price: Some(Coins::new(990_000)),
+            high_price_volume: 10,
+            low_price_volume: 8,
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        };
+        assert_eq!(timeseries_post_tax(WHIP, &entry), Some(Coins::new(990_000)));
+        let entry = TimeseriesItem { avg_high_price: None, ..entry };
+        assert_eq!(timeseries_post_tax(WHIP, &entry), None);
+    }
+}
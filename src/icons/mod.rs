@@ -0,0 +1,154 @@
+use crate::endpoints::mapping::MappingItem;
+use crate::types::ItemId;
+use crate::{Client, Error, ReqwestTransport, Transport};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+pub const WIKI_IMAGES_URL: &str = "https://oldschool.runescape.wiki/images/";
+
+/// Characters MediaWiki leaves unencoded in file URLs, in addition to ASCII letters, digits and
+/// `-_.`.
+const UNENCODED_PUNCTUATION: &[u8] = b";@$!*(),/~:";
+
+/// Returns the URL of a file on the wiki, such as the `icon` of a [`MappingItem`].
+///
+/// Spaces become underscores and other characters are percent-encoded the way MediaWiki does, so
+/// `Zulrah's scales.png` becomes `https://oldschool.runescape.wiki/images/Zulrah%27s_scales.png`.
+pub fn wiki_image_url(file_name: &str) -> String {
+    let mut url = String::with_capacity(WIKI_IMAGES_URL.len() + file_name.len());
+    url.push_str(WIKI_IMAGES_URL);
+    for byte in file_name.trim().bytes() {
+        match byte {
+            b' ' => url.push('_'),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' => url.push(byte as char),
+            byte if UNENCODED_PUNCTUATION.contains(&byte) => url.push(byte as char),
+            byte => write!(url, "%{:02X}", byte).unwrap(),
+        }
+    }
+    url
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IconError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Failed to fetch icon: {0}")]
+    Fetch(#[from] Error),
+}
+
+/// Downloads item icons through a [`Client`] and keeps them in a directory on disk, one file per
+/// item id, so each icon is only downloaded once.
+///
+/// Downloads share the client's retry policy and rate limiter.
+#[derive(Clone)]
+pub struct IconFetcher<T = ReqwestTransport> {
+    client: Client<T>,
+    cache_dir: PathBuf,
+}
+
+impl<T: Transport> IconFetcher<T> {
+    pub fn new(client: Client<T>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self { client, cache_dir: cache_dir.into() }
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Returns the path an item's icon is cached at, whether or not it has been downloaded yet.
+    pub fn cache_path(&self, id: ItemId) -> PathBuf {
+        self.cache_dir.join(format!("{}.png", id.id()))
+    }
+
+    /// Returns the cached icon of an item without downloading it.
+    pub fn cached(&self, id: ItemId) -> Result<Option<Vec<u8>>, IconError> {
+        match std::fs::read(self.cache_path(id)) {
+            Ok(icon) => Ok(Some(icon)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Returns the icon of an item, downloading and caching it if it isn't cached yet.
+    pub async fn icon(&self, item: &MappingItem) -> Result<Vec<u8>, IconError> {
+        if let Some(icon) = self.cached(item.id)? {
+            return Ok(icon);
+        }
+        let icon = self.client.get_bytes(&item.icon_url()).await?;
+        self.store(item.id, &icon)?;
+        Ok(icon)
+    }
+
+    fn store(&self, id: ItemId, icon: &[u8]) -> Result<(), IconError> {
+        std::fs::create_dir_all(&self.cache_dir)?;
+        let path = self.cache_path(id);
+        let temp_path = path.with_extension("png.tmp");
+        std::fs::write(&temp_path, icon)?;
+        std::fs::rename(&temp_path, &path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::mapping::MappingEndpoint;
+    use crate::icons::{wiki_image_url, IconError, IconFetcher};
+    use crate::testing::get_test_client;
+    use crate::transport::{MemoryTransport, Response};
+    use crate::types::ItemId;
+    use crate::{ApiEndpoint, Client, Error, RetryPolicy};
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_wiki_image_url() {
+        assert_eq!(wiki_image_url("Abyssal whip.png"), "https://oldschool.runescape.wiki/images/Abyssal_whip.png");
+        assert_eq!(wiki_image_url("Zulrah's scales.png"), "https://oldschool.runescape.wiki/images/Zulrah%27s_scales.png");
+        assert_eq!(
+            wiki_image_url("Karil's leathertop (100).png"),
+            "https://oldschool.runescape.wiki/images/Karil%27s_leathertop_(100).png"
+        );
+        assert_eq!(wiki_image_url("Fish & chips?.png"), "https://oldschool.runescape.wiki/images/Fish_%26_chips%3F.png");
+        assert_eq!(wiki_image_url("Tome of fire+.png"), "https://oldschool.runescape.wiki/images/Tome_of_fire%2B.png");
+        assert_eq!(wiki_image_url("Ahrim's staff 100%.png"), "https://oldschool.runescape.wiki/images/Ahrim%27s_staff_100%25.png");
+        assert_eq!(wiki_image_url("Bracelet of ethereum (uncharged).png"), "https://oldschool.runescape.wiki/images/Bracelet_of_ethereum_(uncharged).png");
+        assert_eq!(wiki_image_url("Ñ.png"), "https://oldschool.runescape.wiki/images/%C3%91.png");
+    }
+
+    #[tokio::test]
+    async fn test_icon_url() {
+        let client = get_test_client(ApiEndpoint::OldSchoolRuneScape);
+        let mapping = client.mapping().await.unwrap();
+        let scales = mapping.iter().find(|item| item.id == ItemId::new(12934)).unwrap();
+        assert_eq!(scales.icon_url(), "https://oldschool.runescape.wiki/images/Zulrah%27s_scales_5.png");
+    }
+
+    #[tokio::test]
+    async fn test_icon_fetcher() {
+        let cache_dir = std::env::temp_dir().join(format!("osrs-wiki-prices-icons-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&cache_dir);
+        let transport = MemoryTransport::new()
+            .with_response("/images/Zulrah%27s_scales_5.png", Response::new(StatusCode::OK, vec![0x89, b'P', b'N', b'G']))
+            .with_response("/images/Abyssal_whip.png", Response::new(StatusCode::NOT_FOUND, "Not Found"));
+        let client = Client::builder()
+            .user_agent("test")
+            .retry_policy(RetryPolicy::none())
+            .no_rate_limit()
+            .build_with_transport(transport.clone());
+        let mapping = get_test_client(ApiEndpoint::OldSchoolRuneScape).mapping().await.unwrap();
+        let scales = mapping.iter().find(|item| item.id == ItemId::new(12934)).unwrap();
+        let whip = mapping.iter().find(|item| item.id == ItemId::new(4151)).unwrap();
+
+        let fetcher = IconFetcher::new(client, &cache_dir);
+        assert!(fetcher.cached(scales.id).unwrap().is_none());
+        assert_eq!(fetcher.icon(scales).await.unwrap(), b"\x89PNG");
+        assert_eq!(fetcher.icon(scales).await.unwrap(), b"\x89PNG");
+        assert_eq!(transport.requests().len(), 1);
+        assert_eq!(transport.requests()[0].url, "https://oldschool.runescape.wiki/images/Zulrah%27s_scales_5.png");
+        assert_eq!(std::fs::read(cache_dir.join("12934.png")).unwrap(), b"\x89PNG");
+
+        let error = fetcher.icon(whip).await.unwrap_err();
+        assert!(matches!(error, IconError::Fetch(Error::Status { status: StatusCode::NOT_FOUND, .. })));
+        assert!(fetcher.cached(whip.id).unwrap().is_none());
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
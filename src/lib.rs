@@ -1,38 +1,106 @@
 use std::borrow::Cow;
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
 
+pub mod alerts;
+pub mod analysis;
+#[cfg(feature = "sqlite")]
+pub mod archive;
+pub mod backfill;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod builder;
+pub mod cache;
+pub mod catalog;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod endpoints;
+mod error;
+pub mod ge_tax;
+pub mod icons;
+pub mod rate_limit;
+mod request;
+pub mod retry;
+pub mod runtime;
+pub mod transport;
 pub mod types;
-#[cfg(test)]
+pub mod watch;
+#[cfg(any(test, feature = "testing"))]
 pub mod testing;
 
+pub use builder::ClientBuilder;
+pub use cache::ResponseCache;
+pub use error::Error;
+pub use rate_limit::RateLimiter;
+pub use retry::RetryPolicy;
+pub use runtime::Timer;
+pub use transport::{ReqwestTransport, Transport};
+
 const BASE_URL: &str = "prices.runescape.wiki/api/v1";
 
-pub struct Client {
-    http_client: reqwest::Client,
+#[derive(Clone)]
+pub struct Client<T = ReqwestTransport> {
+    transport: T,
     base_url: String,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    cache: Option<ResponseCache>,
+    timer: Arc<dyn Timer>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ClientNewError {
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
+    #[error("A user agent is required when no HTTP client is provided")]
+    MissingUserAgent,
 }
 
 impl Client {
     pub fn try_new(user_agent: Cow<str>, api_endpoint: ApiEndpoint) -> Result<Self, ClientNewError> {
-        let http_client = reqwest::Client::builder()
-            .user_agent(user_agent.as_ref())
-            .build()?;
-        let base_url = format!("https://{}/{}", BASE_URL, api_endpoint);
-        Ok(Self { http_client, base_url })
+        Self::builder()
+            .user_agent(user_agent)
+            .api_endpoint(api_endpoint)
+            .build()
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+}
+
+impl<T> Client<T> {
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    pub fn cache(&self) -> Option<&ResponseCache> {
+        self.cache.as_ref()
+    }
+
+    pub fn timer(&self) -> &dyn Timer {
+        self.timer.as_ref()
     }
 }
 
 #[derive(Debug, Clone, Copy)]
-#[derive(PartialEq)]
+#[derive(PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum ApiEndpoint {
+    #[serde(rename = "osrs")]
     OldSchoolRuneScape,
+    #[serde(rename = "dmm")]
     DeadmanArmageddon,
 }
 
@@ -0,0 +1,125 @@
+use crate::Timer;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A GCRA rate limiter shared by every clone of it and of the [`Client`](crate::Client) using it.
+///
+/// Requests are allowed at a steady rate of one per emission interval, with up to `burst`
+/// requests going out back to back when the limiter has been idle. Callers over budget are
+/// queued in the order they asked for a slot.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    emission_interval: Duration,
+    burst: u32,
+    theoretical_arrival: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(60, Duration::from_secs(60), 10)
+    }
+}
+
+impl RateLimiter {
+    /// Allows `requests` requests every `per`, with bursts of up to `burst` requests.
+    pub fn new(requests: u32, per: Duration, burst: u32) -> Self {
+        Self {
+            emission_interval: per / requests.max(1),
+            burst: burst.max(1),
+            theoretical_arrival: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn emission_interval(&self) -> Duration {
+        self.emission_interval
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Returns how many requests could be sent right now without waiting.
+    pub fn available(&self) -> u32 {
+        self.available_at(Instant::now())
+    }
+
+    /// Reserves a slot for one request and returns how long the caller has to wait before sending it.
+    pub fn reserve(&self) -> Duration {
+        self.reserve_at(Instant::now())
+    }
+
+    pub(crate) async fn acquire(&self, timer: &dyn Timer) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            timer.sleep(wait).await;
+        }
+    }
+
+    fn tolerance(&self) -> Duration {
+        self.emission_interval * (self.burst - 1)
+    }
+
+    fn available_at(&self, now: Instant) -> u32 {
+        let theoretical_arrival = self.theoretical_arrival.lock().unwrap();
+        let backlog = match *theoretical_arrival {
+            Some(theoretical_arrival) => theoretical_arrival.saturating_duration_since(now),
+            None => Duration::ZERO,
+        };
+        let remaining = (self.tolerance() + self.emission_interval).saturating_sub(backlog);
+        (remaining.as_nanos() / self.emission_interval.as_nanos().max(1)) as u32
+    }
+
+    fn reserve_at(&self, now: Instant) -> Duration {
+        let mut theoretical_arrival = self.theoretical_arrival.lock().unwrap();
+        let arrival = match *theoretical_arrival {
+            Some(theoretical_arrival) if theoretical_arrival > now => theoretical_arrival,
+            _ => now,
+        };
+        let allowed_at = arrival.checked_sub(self.tolerance()).unwrap_or(now);
+        *theoretical_arrival = Some(arrival + self.emission_interval);
+        allowed_at.saturating_duration_since(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rate_limit::RateLimiter;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_burst_then_steady_rate() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(1), 3);
+        let now = Instant::now();
+        assert_eq!(limiter.available_at(now), 3);
+        assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+        assert_eq!(limiter.reserve_at(now), Duration::ZERO);
+        assert_eq!(limiter.available_at(now), 0);
+        assert_eq!(limiter.reserve_at(now), Duration::from_secs(1));
+        assert_eq!(limiter.reserve_at(now), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_budget_recovers() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(1), 4);
+        let now = Instant::now();
+        for _ in 0..4 {
+            limiter.reserve_at(now);
+        }
+        assert_eq!(limiter.available_at(now), 0);
+        assert_eq!(limiter.available_at(now + Duration::from_millis(500)), 1);
+        assert_eq!(limiter.available_at(now + Duration::from_secs(10)), 4);
+        assert_eq!(limiter.reserve_at(now + Duration::from_secs(10)), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_shared_between_clones() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(1), 2);
+        let clone = limiter.clone();
+        let now = Instant::now();
+        limiter.reserve_at(now);
+        clone.reserve_at(now);
+        assert_eq!(limiter.available_at(now), 0);
+        assert_eq!(clone.reserve_at(now), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,318 @@
+use crate::cache::{CachedEndpoint, ResponseCache};
+use crate::transport::{Request, Response, Transport};
+use crate::{Client, Error};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+const BODY_SNIPPET_LENGTH: usize = 512;
+
+#[derive(serde::Deserialize)]
+struct ApiErrorBody {
+    error: String,
+}
+
+impl<T: Transport> Client<T> {
+    pub(crate) async fn get_json<V: DeserializeOwned>(&self, url: &str) -> Result<V, Error> {
+        self.with_retries(|| self.get_json_once(url)).await
+    }
+
+    pub(crate) async fn get_json_cached<V>(&self, endpoint: CachedEndpoint, url: &str) -> Result<V, Error>
+    where
+        V: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let Some(cache) = &self.cache else {
+            return self.get_json(url).await;
+        };
+        if let Some(value) = cache.fresh(endpoint, url) {
+            return Ok(value);
+        }
+        self.with_retries(|| self.get_json_conditional(cache, url)).await
+    }
+
+    async fn with_retries<V, F, Fut>(&self, mut request: F) -> Result<V, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output=Result<V, Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let error = match request().await {
+                Ok(value) => return Ok(value),
+                Err(error) if !self.retry_policy.is_retryable(&error) => return Err(error),
+                Err(error) => error,
+            };
+            if attempt >= self.retry_policy.max_attempts {
+                if attempt == 1 {
+                    return Err(error);
+                }
+                return Err(Error::RetriesExhausted { attempts: attempt, source: Box::new(error) });
+            }
+            let Some(delay) = self.retry_policy.delay(attempt, &error) else {
+                return Err(error);
+            };
+            self.timer.sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Fetches a raw response body, such as an image, with the same retries and rate limiting as
+    /// API requests.
+    pub(crate) async fn get_bytes(&self, url: &str) -> Result<Vec<u8>, Error> {
+        self.with_retries(|| async {
+            let response = self.send(url, HeaderMap::new()).await?;
+            check_status(response.status, &response.headers, &response.body)?;
+            Ok(response.body)
+        })
+        .await
+    }
+
+    async fn get_json_once<V: DeserializeOwned>(&self, url: &str) -> Result<V, Error> {
+        let response = self.send(url, HeaderMap::new()).await?;
+        decode_response(response.status, &response.headers, &response.body)
+    }
+
+    async fn get_json_conditional<V>(&self, cache: &ResponseCache, url: &str) -> Result<V, Error>
+    where
+        V: DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let response = self.send(url, cache.conditional_headers(url)).await?;
+        if response.status == StatusCode::NOT_MODIFIED
+            && let Some(value) = cache.revalidate(url)
+        {
+            return Ok(value);
+        }
+        let value: V = decode_response(response.status, &response.headers, &response.body)?;
+        cache.store(url, &response.headers, value.clone());
+        Ok(value)
+    }
+
+    async fn send(&self, url: &str, headers: HeaderMap) -> Result<Response, Error> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(self.timer.as_ref()).await;
+        }
+        let request = Request { url: url.to_string(), headers };
+        Ok(self.transport.get(request).await?)
+    }
+}
+
+pub(crate) fn decode_response<T: DeserializeOwned>(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Result<T, Error> {
+    check_status(status, headers, body)?;
+    let deserializer = &mut serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(deserializer).map_err(|error| {
+        if let Ok(api_error) = serde_json::from_slice::<ApiErrorBody>(body) {
+            return Error::Api { message: api_error.error, status: None };
+        }
+        Error::Decode { path: error.path().to_string(), source: error.into_inner() }
+    })
+}
+
+fn check_status(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Result<(), Error> {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(Error::RateLimited { retry_after: parse_retry_after(headers, Utc::now()) });
+    }
+    if !status.is_success() {
+        if let Ok(api_error) = serde_json::from_slice::<ApiErrorBody>(body) {
+            return Err(Error::Api { message: api_error.error, status: Some(status) });
+        }
+        return Err(Error::Status { status, body: body_snippet(body) });
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date_time = DateTime::parse_from_rfc2822(value).ok()?;
+    (date_time.with_timezone(&Utc) - now).to_std().ok()
+}
+
+fn body_snippet(body: &[u8]) -> String {
+    let body = String::from_utf8_lossy(body);
+    match body.char_indices().nth(BODY_SNIPPET_LENGTH) {
+        Some((index, _)) => format!("{}...", &body[..index]),
+        None => body.into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::latest::{LatestEndpoint, LatestResponse};
+    use crate::request::{decode_response, parse_retry_after};
+    use crate::testing::{http_response, start_mock_server};
+    use crate::{Client, Error, RateLimiter, RetryPolicy};
+    use chrono::{TimeZone, Utc};
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    #[test]
+    fn test_decode_success() {
+        let body = br#"{"data":{"4151":{"high":1500000,"highTime":1697059200,"low":1490000,"lowTime":1697059100}}}"#;
+        let result: LatestResponse = decode_response(StatusCode::OK, &HeaderMap::new(), body).unwrap();
+        assert_eq!(result.data.len(), 1);
+    }
+
+    #[test]
+    fn test_decode_status() {
+        let body = b"<html>Bad Gateway</html>";
+        let result = decode_response::<LatestResponse>(StatusCode::BAD_GATEWAY, &HeaderMap::new(), body);
+        match result {
+            Err(Error::Status { status, body }) => {
+                assert_eq!(status, StatusCode::BAD_GATEWAY);
+                assert_eq!(body, "<html>Bad Gateway</html>");
+            }
+            _ => panic!("Expected a status error"),
+        }
+    }
+
+    #[test]
+    fn test_decode_status_snippet() {
+        let body = "x".repeat(2048);
+        let result = decode_response::<LatestResponse>(StatusCode::INTERNAL_SERVER_ERROR, &HeaderMap::new(), body.as_bytes());
+        match result {
+            Err(Error::Status { body, .. }) => assert_eq!(body.len(), 515),
+            _ => panic!("Expected a status error"),
+        }
+    }
+
+    #[test]
+    fn test_decode_rate_limited() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        let result = decode_response::<LatestResponse>(StatusCode::TOO_MANY_REQUESTS, &headers, b"");
+        match result {
+            Err(Error::RateLimited { retry_after }) => assert_eq!(retry_after, Some(Duration::from_secs(30))),
+            _ => panic!("Expected a rate limited error"),
+        }
+    }
+
+    #[test]
+    fn test_decode_api_error() {
+        let body = br#"{"error":"Invalid timestamp"}"#;
+        let result = decode_response::<LatestResponse>(StatusCode::OK, &HeaderMap::new(), body);
+        assert!(matches!(result, Err(Error::Api { message, status: None }) if message == "Invalid timestamp"));
+
+        let result = decode_response::<LatestResponse>(StatusCode::BAD_REQUEST, &HeaderMap::new(), body);
+        let error = result.unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert!(matches!(error, Error::Api { message, .. } if message == "Invalid timestamp"));
+    }
+
+    #[test]
+    fn test_decode_error_path() {
+        let body = br#"{"data":{"4151":{"high":"expensive","highTime":1697059200,"low":1490000,"lowTime":1697059100}}}"#;
+        let result = decode_response::<LatestResponse>(StatusCode::OK, &HeaderMap::new(), body);
+        match result {
+            Err(Error::Decode { path, .. }) => assert_eq!(path, "data.4151.high"),
+            _ => panic!("Expected a decode error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after_date() {
+        let now = Utc.timestamp_opt(1697059200, 0).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 11 Oct 2023 21:21:00 GMT"));
+        assert_eq!(parse_retry_after(&headers, now), Some(Duration::from_secs(60)));
+    }
+
+    fn retry_client(base_url: &str, max_attempts: u32) -> Client {
+        let retry_policy = RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            ..RetryPolicy::default()
+        };
+        Client::builder()
+            .user_agent("osrs-wiki-prices-rs")
+            .base_url(base_url)
+            .retry_policy(retry_policy)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        let body = r#"{"data":{"4151":{"high":1500000,"highTime":1697059200,"low":1490000,"lowTime":1697059100}}}"#;
+        let server = start_mock_server(vec![
+            http_response(503, &[], "unavailable"),
+            http_response(429, &[("retry-after", "0")], ""),
+            http_response(200, &[], body),
+        ])
+        .await;
+        let client = retry_client(&server.base_url, 3);
+        let result = client.latest().await;
+        assert!(result.is_ok());
+        assert_eq!(server.requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhausted() {
+        let server = start_mock_server(vec![
+            http_response(502, &[], "bad gateway"),
+            http_response(502, &[], "bad gateway"),
+        ])
+        .await;
+        let client = retry_client(&server.base_url, 2);
+        let error = client.latest().await.unwrap_err();
+        assert_eq!(error.attempts(), 2);
+        assert_eq!(error.status(), Some(StatusCode::BAD_GATEWAY));
+        assert!(matches!(error.last_error(), Error::Status { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_requests() {
+        let body = r#"{"data":{}}"#;
+        let server = start_mock_server(vec![http_response(200, &[], body); 3]).await;
+        let rate_limiter = RateLimiter::new(1, Duration::from_millis(100), 1);
+        let client = Client::builder()
+            .user_agent("osrs-wiki-prices-rs")
+            .base_url(&server.base_url)
+            .rate_limiter(rate_limiter.clone())
+            .build()
+            .unwrap();
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            client.clone().latest().await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(rate_limiter.available(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_at_non_retryable_error() {
+        let server = start_mock_server(vec![
+            http_response(503, &[], "unavailable"),
+            http_response(404, &[], "not found"),
+        ])
+        .await;
+        let client = retry_client(&server.base_url, 3);
+        let error = client.latest().await.unwrap_err();
+        assert!(matches!(error, Error::Status { status: StatusCode::NOT_FOUND, .. }));
+        assert_eq!(server.requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_beyond_max_delay() {
+        let server = start_mock_server(vec![http_response(429, &[("retry-after", "86400")], "")]).await;
+        let client = retry_client(&server.base_url, 3);
+        let error = client.latest().await.unwrap_err();
+        assert!(matches!(error, Error::RateLimited { retry_after: Some(retry_after) } if retry_after == Duration::from_secs(86400)));
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_not_retryable() {
+        let server = start_mock_server(vec![http_response(404, &[], "not found")]).await;
+        let client = retry_client(&server.base_url, 3);
+        let error = client.latest().await.unwrap_err();
+        assert_eq!(error.attempts(), 1);
+        assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+        assert_eq!(server.requests.lock().unwrap().len(), 1);
+    }
+}
\ No newline at end of file
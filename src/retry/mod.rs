@@ -0,0 +1,155 @@
+use crate::Error;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// Controls how failed requests are retried.
+///
+/// Delays grow exponentially from the base delay and are capped at the maximum delay. With jitter
+/// enabled, a random delay between half and the full backoff is used so concurrent clients do not
+/// retry in lockstep.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    pub retryable_statuses: Vec<StatusCode>,
+    /// Whether the delay requested by a `Retry-After` header takes precedence over the backoff.
+    /// Requests asking for a longer wait than `max_delay` are not retried.
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retryable_statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that performs every request exactly once.
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        match error {
+            Error::Transport(error) => error.is_transient(),
+            Error::Status { status, .. } | Error::Api { status: Some(status), .. } => self.retryable_statuses.contains(status),
+            Error::RateLimited { .. } => self.retryable_statuses.contains(&StatusCode::TOO_MANY_REQUESTS),
+            _ => false,
+        }
+    }
+
+    /// Returns how long to wait after the given (1-based) failed attempt, or `None` if the server
+    /// asked to wait longer than `max_delay`.
+    pub fn delay(&self, attempt: u32, error: &Error) -> Option<Duration> {
+        if let Error::RateLimited { retry_after: Some(retry_after) } = error
+            && self.respect_retry_after
+        {
+            return (*retry_after <= self.max_delay).then_some(*retry_after);
+        }
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = self.base_delay.saturating_mul(1 << exponent).min(self.max_delay);
+        if self.jitter {
+            let half = backoff / 2;
+            Some(half + backoff.mul_f64(fastrand::f64() / 2.0))
+        } else {
+            Some(backoff)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::retry::RetryPolicy;
+    use crate::Error;
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    fn status_error(status: StatusCode) -> Error {
+        Error::Status { status, body: String::new() }
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retryable(&status_error(StatusCode::SERVICE_UNAVAILABLE)));
+        assert!(policy.is_retryable(&Error::RateLimited { retry_after: None }));
+        assert!(!policy.is_retryable(&status_error(StatusCode::NOT_FOUND)));
+        assert!(!policy.is_retryable(&Error::Api { message: "Invalid request".to_string(), status: None }));
+        assert!(policy.is_retryable(&Error::Api { message: "Try again".to_string(), status: Some(StatusCode::SERVICE_UNAVAILABLE) }));
+        assert!(!policy.is_retryable(&Error::ItemNotFound));
+
+        let policy = RetryPolicy {
+            retryable_statuses: vec![StatusCode::NOT_FOUND],
+            ..RetryPolicy::default()
+        };
+        assert!(policy.is_retryable(&status_error(StatusCode::NOT_FOUND)));
+        assert!(!policy.is_retryable(&Error::RateLimited { retry_after: None }));
+    }
+
+    #[test]
+    fn test_delay_backoff() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let error = status_error(StatusCode::BAD_GATEWAY);
+        assert_eq!(policy.delay(1, &error), Some(Duration::from_millis(100)));
+        assert_eq!(policy.delay(2, &error), Some(Duration::from_millis(200)));
+        assert_eq!(policy.delay(3, &error), Some(Duration::from_millis(350)));
+        assert_eq!(policy.delay(40, &error), Some(Duration::from_millis(350)));
+    }
+
+    #[test]
+    fn test_delay_jitter() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            ..RetryPolicy::default()
+        };
+        let error = status_error(StatusCode::BAD_GATEWAY);
+        for _ in 0..100 {
+            let delay = policy.delay(2, &error).unwrap();
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_delay_retry_after() {
+        let error = Error::RateLimited { retry_after: Some(Duration::from_secs(7)) };
+        assert_eq!(RetryPolicy::default().delay(1, &error), Some(Duration::from_secs(7)));
+        let policy = RetryPolicy {
+            respect_retry_after: false,
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.delay(1, &error), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_delay_retry_after_beyond_max_delay() {
+        let error = Error::RateLimited { retry_after: Some(Duration::from_secs(86400)) };
+        assert_eq!(RetryPolicy::default().delay(1, &error), None);
+        let error = Error::RateLimited { retry_after: Some(Duration::from_secs(30)) };
+        assert_eq!(RetryPolicy::default().delay(1, &error), Some(Duration::from_secs(30)));
+    }
+
+}
@@ -0,0 +1,47 @@
+use std::pin::Pin;
+use std::time::Duration;
+
+pub type Sleep = Pin<Box<dyn Future<Output=()> + Send>>;
+
+/// Provides the delays used for retries, rate limiting and polling, so the crate does not depend
+/// on a specific async runtime.
+pub trait Timer: Send + Sync {
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// A timer backed by `futures-timer`, which works on any executor.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FuturesTimer;
+
+impl Timer for FuturesTimer {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(futures_timer::Delay::new(duration))
+    }
+}
+
+/// A timer backed by `tokio::time`, which must be used from within a Tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{FuturesTimer, Timer};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_futures_timer_without_tokio_time() {
+        // The runtime is built without `enable_time`, so a Tokio-based sleep would panic here.
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let start = Instant::now();
+        runtime.block_on(FuturesTimer.sleep(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
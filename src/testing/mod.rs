@@ -1,6 +1,175 @@
-use crate::{ApiEndpoint, Client};
+//! Helpers for testing code built on the client without network access.
+//!
+//! [`cassette_client`] replays a fixture recorded with [`CassetteTransport`], or records it
+//! against the live API when `OSRS_WIKI_PRICES_RECORD` is set. The crate's own tests instead
+//! replay `tests/fixtures/osrs-synthetic.json`, a small hand-written cassette with a handful of
+//! items. Its payloads are not real API responses.
 
-pub fn get_test_client(api_endpoint: ApiEndpoint) -> Client {
-    let user_agent = "osrs-wiki-prices-rs";
-    Client::try_new(user_agent.into(), api_endpoint).expect("Failed to create test client")
-}
\ No newline at end of file
+use crate::transport::cassette::{CassetteError, CassetteTransport};
+use crate::runtime::Sleep;
+use crate::{ApiEndpoint, Client, ReqwestTransport, Timer};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+#[cfg(test)]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(test)]
+use tokio::net::TcpListener;
+
+/// Set to record cassettes opened with [`cassette_transport`] against the live API.
+pub const RECORD_ENV: &str = "OSRS_WIKI_PRICES_RECORD";
+
+/// Opens the cassette at `path` for replay, or for recording through `http_client` when
+/// [`RECORD_ENV`] is set.
+pub fn cassette_transport(path: impl AsRef<Path>, http_client: reqwest::Client) -> Result<CassetteTransport, CassetteError> {
+    let path = path.as_ref();
+    if std::env::var_os(RECORD_ENV).is_some() {
+        CassetteTransport::record(path, ReqwestTransport::new(http_client))
+    } else {
+        CassetteTransport::replay_file(path)
+    }
+}
+
+/// Builds a client without rate limiting on top of [`cassette_transport`].
+pub fn cassette_client(
+    path: impl AsRef<Path>,
+    http_client: reqwest::Client,
+    api_endpoint: ApiEndpoint,
+) -> Result<Client<CassetteTransport>, CassetteError> {
+    Ok(Client::builder()
+        .api_endpoint(api_endpoint)
+        .no_rate_limit()
+        .build_with_transport(cassette_transport(path, http_client)?))
+}
+
+#[cfg(test)]
+pub(crate) fn get_test_transport(api_endpoint: ApiEndpoint) -> CassetteTransport {
+    use std::collections::HashMap;
+    use std::sync::OnceLock;
+
+    static TRANSPORTS: OnceLock<Mutex<HashMap<String, CassetteTransport>>> = OnceLock::new();
+    let path = format!("{}/tests/fixtures/{}-synthetic.json", env!("CARGO_MANIFEST_DIR"), api_endpoint);
+    let mut transports = TRANSPORTS.get_or_init(Default::default).lock().unwrap();
+    let transport = transports
+        .entry(path.clone())
+        .or_insert_with(|| CassetteTransport::replay_file(&path).expect("Failed to load cassette"));
+    transport.with_new_log()
+}
+
+#[cfg(test)]
+pub(crate) fn get_test_client(api_endpoint: ApiEndpoint) -> Client<CassetteTransport> {
+    Client::builder()
+        .api_endpoint(api_endpoint)
+        .no_rate_limit()
+        .build_with_transport(get_test_transport(api_endpoint))
+}
+
+/// Returns the fixture response body for a path such as `/latest?id=4151`.
+#[cfg(test)]
+pub(crate) fn get_fixture_body(api_endpoint: ApiEndpoint, path: &str) -> serde_json::Value {
+    let url = format!("https://prices.runescape.wiki/api/v1/{}{}", api_endpoint, path);
+    let cassette = get_test_transport(api_endpoint).cassette();
+    cassette.find(&url).unwrap_or_else(|| panic!("No recording for {}", path)).body.clone()
+}
+
+/// Asserts that `json` deserializes into `V` and serializes back to the same JSON.
+pub fn assert_round_trip<V>(json: serde_json::Value) -> V
+where
+    V: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let value: V = serde_json::from_value(json.clone()).unwrap();
+    let serialized = serde_json::to_value(&value).unwrap();
+    assert_eq!(serialized, json);
+    assert_eq!(serde_json::from_value::<V>(serialized).unwrap(), value);
+    value
+}
+
+/// A timer that returns immediately and records the requested durations.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingTimer {
+    pub sleeps: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl Timer for RecordingTimer {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.sleeps.lock().unwrap().push(duration);
+        Box::pin(std::future::ready(()))
+    }
+}
+
+/// A local HTTP server answering each connection with the next canned response.
+#[cfg(test)]
+pub(crate) struct MockServer {
+    pub base_url: String,
+    pub requests: Arc<Mutex<Vec<String>>>,
+}
+
+#[cfg(test)]
+pub(crate) fn http_response(status: u16, headers: &[(&str, &str)], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {} Mock\r\ncontent-length: {}\r\nconnection: close\r\n", status, body.len());
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    response
+}
+
+#[cfg(test)]
+pub(crate) async fn start_mock_server(responses: Vec<String>) -> MockServer {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind mock server");
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = requests.clone();
+    tokio::spawn(async move {
+        for response in responses {
+            let Ok((mut stream, _)) = listener.accept().await else { return };
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !is_complete_request(&request) {
+                match stream.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => request.extend_from_slice(&buffer[..read]),
+                }
+            }
+            received.lock().unwrap().push(String::from_utf8_lossy(&request).into_owned());
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        }
+    });
+    MockServer { base_url, requests }
+}
+
+/// Returns whether `request` holds the full head and, per its `content-length`, the full body.
+#[cfg(test)]
+fn is_complete_request(request: &[u8]) -> bool {
+    let Some(head_end) = request.windows(4).position(|window| window == b"\r\n\r\n") else {
+        return false;
+    };
+    let head = String::from_utf8_lossy(&request[..head_end]);
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    request.len() >= head_end + 4 + content_length
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::latest::LatestEndpoint;
+    use crate::testing::cassette_client;
+    use crate::types::ItemId;
+    use crate::ApiEndpoint;
+
+    #[tokio::test]
+    async fn test_cassette_client_replays() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/osrs-synthetic.json");
+        let client = cassette_client(path, reqwest::Client::new(), ApiEndpoint::OldSchoolRuneScape).unwrap();
+        assert!(client.latest_by_id(ItemId::new(4151)).await.is_ok());
+        assert!(cassette_client("missing.json", reqwest::Client::new(), ApiEndpoint::OldSchoolRuneScape).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::transport::{normalize_query, Request, Response, Transport, TransportError, TransportErrorKind};
+use crate::ReqwestTransport;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, SET_COOKIE};
+use reqwest::{StatusCode, Url};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Interaction {
+    pub url: String,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// The response body, stored as JSON when it parses as such to keep fixture files readable.
+    pub body: serde_json::Value,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CassetteError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        let contents = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CassetteError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = serde_json::to_vec_pretty(self)?;
+        contents.push(b'\n');
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Returns the interaction recorded for the URL's path and query, ignoring the order of
+    /// query parameters.
+    pub fn find(&self, url: &str) -> Option<&Interaction> {
+        let key = interaction_key(url)?;
+        self.interactions
+            .iter()
+            .find(|interaction| interaction_key(&interaction.url).as_deref() == Some(key.as_str()))
+    }
+
+    /// Adds an interaction, replacing any earlier one recorded for the same URL.
+    pub fn insert(&mut self, interaction: Interaction) {
+        let key = interaction_key(&interaction.url);
+        self.interactions.retain(|existing| interaction_key(&existing.url) != key);
+        self.interactions.push(interaction);
+    }
+}
+
+impl Interaction {
+    pub fn new(url: &str, response: &Response) -> Self {
+        let headers = response
+            .headers
+            .iter()
+            .filter(|(name, _)| *name != SET_COOKIE)
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = serde_json::from_slice(&response.body)
+            .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&response.body).into_owned()));
+        Self { url: url.to_string(), status: response.status.as_u16(), headers, body }
+    }
+
+    pub fn to_response(&self) -> Response {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+                headers.insert(name, value);
+            }
+        }
+        let body = match &self.body {
+            serde_json::Value::String(body) => body.clone().into_bytes(),
+            body => body.to_string().into_bytes(),
+        };
+        Response {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            headers,
+            body,
+        }
+    }
+}
+
+enum Mode<T> {
+    Record { inner: T, path: PathBuf },
+    Replay,
+}
+
+/// A transport that records responses into a cassette file or replays them from one.
+///
+/// In record mode every request is forwarded to the inner transport and the response is written
+/// to the cassette file right away, replacing earlier recordings of the same URL. In replay mode
+/// responses are served from the cassette, and requests without a recording fail.
+#[derive(Clone)]
+pub struct CassetteTransport<T = ReqwestTransport> {
+    mode: Arc<Mode<T>>,
+    cassette: Arc<Mutex<Cassette>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl<T> std::fmt::Debug for CassetteTransport<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self.mode.as_ref() {
+            Mode::Record { .. } => "record",
+            Mode::Replay => "replay",
+        };
+        f.debug_struct("CassetteTransport").field("mode", &mode).finish()
+    }
+}
+
+impl CassetteTransport {
+    pub fn replay(cassette: Cassette) -> Self {
+        Self {
+            mode: Arc::new(Mode::Replay),
+            cassette: Arc::new(Mutex::new(cassette)),
+            requests: Arc::default(),
+        }
+    }
+
+    pub fn replay_file(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        Ok(Self::replay(Cassette::load(path)?))
+    }
+}
+
+impl<T> CassetteTransport<T> {
+    /// Records into the cassette at `path`, keeping interactions already stored in it.
+    pub fn record(path: impl Into<PathBuf>, inner: T) -> Result<Self, CassetteError> {
+        let path = path.into();
+        let cassette = if path.exists() { Cassette::load(&path)? } else { Cassette::default() };
+        Ok(Self {
+            mode: Arc::new(Mode::Record { inner, path }),
+            cassette: Arc::new(Mutex::new(cassette)),
+            requests: Arc::default(),
+        })
+    }
+
+    pub fn cassette(&self) -> Cassette {
+        self.cassette.lock().unwrap().clone()
+    }
+
+    /// Returns every request received so far.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns a transport sharing this one's cassette, with its own request log.
+    #[cfg(test)]
+    pub(crate) fn with_new_log(&self) -> Self {
+        Self {
+            mode: self.mode.clone(),
+            cassette: self.cassette.clone(),
+            requests: Arc::default(),
+        }
+    }
+}
+
+impl<T: Transport> Transport for CassetteTransport<T> {
+    async fn get(&self, request: Request) -> Result<Response, TransportError> {
+        self.requests.lock().unwrap().push(request.clone());
+        match self.mode.as_ref() {
+            Mode::Record { inner, path } => {
+                let url = request.url.clone();
+                let response = inner.get(request).await?;
+                let mut cassette = self.cassette.lock().unwrap();
+                cassette.insert(Interaction::new(&url, &response));
+                cassette.save(path).map_err(|error| TransportError::new(TransportErrorKind::Other, error))?;
+                Ok(response)
+            }
+            Mode::Replay => {
+                let cassette = self.cassette.lock().unwrap();
+                match cassette.find(&request.url) {
+                    Some(interaction) => Ok(interaction.to_response()),
+                    None => Err(TransportError::new(
+                        TransportErrorKind::Other,
+                        format!("No recorded response for {}", request.url),
+                    )),
+                }
+            }
+        }
+    }
+}
+
+fn interaction_key(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    Some(format!("{}?{}", url.path(), normalize_query(url.query().unwrap_or_default())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transport::cassette::{Cassette, CassetteTransport};
+    use crate::transport::{MemoryTransport, Request, Transport};
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+
+    fn request(url: &str) -> Request {
+        Request { url: url.to_string(), headers: HeaderMap::new() }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("osrs-wiki-prices-cassette-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let inner = MemoryTransport::new()
+            .with_json("/latest", r#"{"data":{}}"#)
+            .with_json("/timeseries?timestep=5m&id=4151", r#"{"data":[]}"#);
+
+        let recorder = CassetteTransport::record(&path, inner.clone()).unwrap();
+        recorder.get(request("https://prices.runescape.wiki/api/v1/osrs/latest")).await.unwrap();
+        recorder.get(request("https://prices.runescape.wiki/api/v1/osrs/latest")).await.unwrap();
+        recorder.get(request("https://prices.runescape.wiki/api/v1/osrs/timeseries?timestep=5m&id=4151")).await.unwrap();
+        assert_eq!(inner.requests().len(), 3);
+
+        let cassette = Cassette::load(&path).unwrap();
+        assert_eq!(cassette.interactions.len(), 2);
+        assert_eq!(cassette.interactions[0].body, serde_json::json!({"data": {}}));
+
+        let replay = CassetteTransport::replay_file(&path).unwrap();
+        let response = replay.get(request("https://prices.runescape.wiki/api/v1/osrs/timeseries?id=4151&timestep=5m")).await.unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body, br#"{"data":[]}"#);
+        assert!(replay.get(request("https://prices.runescape.wiki/api/v1/osrs/mapping")).await.is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+use reqwest::header::HeaderMap;
+use reqwest::{StatusCode, Url};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+pub mod cassette;
+
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub url: String,
+    pub headers: HeaderMap,
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn json(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(StatusCode::OK, body)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    Connect,
+    Timeout,
+    Request,
+    Body,
+    Other,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("{source}")]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    #[source]
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl TransportError {
+    pub fn new(kind: TransportErrorKind, source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self { kind, source: source.into() }
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+
+    /// Whether the failure is likely transient, such as a refused connection or a timeout.
+    pub fn is_transient(&self) -> bool {
+        self.kind != TransportErrorKind::Other
+    }
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(error: reqwest::Error) -> Self {
+        let kind = if error.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if error.is_connect() {
+            TransportErrorKind::Connect
+        } else if error.is_body() || error.is_decode() {
+            TransportErrorKind::Body
+        } else if error.is_request() {
+            TransportErrorKind::Request
+        } else {
+            TransportErrorKind::Other
+        };
+        Self::new(kind, error)
+    }
+}
+
+/// Sends the `GET` requests issued by [`Client`](crate::Client).
+pub trait Transport {
+    fn get(&self, request: Request) -> impl Future<Output=Result<Response, TransportError>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    http_client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(http_client: reqwest::Client) -> Self {
+        Self { http_client }
+    }
+
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.http_client
+    }
+}
+
+impl Transport for ReqwestTransport {
+    async fn get(&self, request: Request) -> Result<Response, TransportError> {
+        let response = self.http_client.get(request.url).headers(request.headers).send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+        Ok(Response { status, headers, body })
+    }
+}
+
+/// A transport serving canned responses from memory.
+///
+/// Routes are matched against the end of the request path, so `/latest` answers requests for
+/// `https://prices.runescape.wiki/api/v1/osrs/latest` as well as for any other base URL. A route
+/// with a query string only answers requests with the same query parameters (in any order), while
+/// a route without one answers any query on that path. When several responses are registered for
+/// a route they are served in order, with the last one repeating. Unmatched requests receive a
+/// `404 Not Found`.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    routes: Arc<Mutex<HashMap<String, VecDeque<Response>>>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_json(self, route: &str, body: impl Into<Vec<u8>>) -> Self {
+        self.with_response(route, Response::json(body))
+    }
+
+    pub fn with_response(self, route: &str, response: Response) -> Self {
+        self.push_response(route, response);
+        self
+    }
+
+    pub fn push_response(&self, route: &str, response: Response) {
+        let (path, query) = split_route(route);
+        let key = route_key(path, &normalize_query(query));
+        self.routes.lock().unwrap().entry(key).or_default().push_back(response);
+    }
+
+    /// Returns every request received so far.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, request: &Request) -> Response {
+        let Ok(url) = Url::parse(&request.url) else {
+            return Response::new(StatusCode::BAD_REQUEST, "Invalid URL");
+        };
+        let query = normalize_query(url.query().unwrap_or_default());
+        let mut routes = self.routes.lock().unwrap();
+        let key = routes
+            .keys()
+            .filter(|key| {
+                let (path, route_query) = split_route(key);
+                path_matches(url.path(), path) && (route_query.is_empty() || route_query == query)
+            })
+            .max_by_key(|key| (key.contains('?'), key.len()))
+            .cloned();
+        let Some(responses) = key.and_then(|key| routes.get_mut(&key)) else {
+            return Response::new(StatusCode::NOT_FOUND, "Not Found");
+        };
+        if responses.len() > 1 {
+            responses.pop_front().unwrap()
+        } else {
+            responses.front().cloned().unwrap_or_else(|| Response::new(StatusCode::NOT_FOUND, "Not Found"))
+        }
+    }
+}
+
+impl Transport for MemoryTransport {
+    async fn get(&self, request: Request) -> Result<Response, TransportError> {
+        let response = self.respond(&request);
+        self.requests.lock().unwrap().push(request);
+        Ok(response)
+    }
+}
+
+fn split_route(route: &str) -> (&str, &str) {
+    route.split_once('?').unwrap_or((route, ""))
+}
+
+fn route_key(path: &str, query: &str) -> String {
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, query)
+    }
+}
+
+pub(crate) fn normalize_query(query: &str) -> String {
+    let mut pairs: Vec<&str> = query.split('&').filter(|pair| !pair.is_empty()).collect();
+    pairs.sort_unstable();
+    pairs.join("&")
+}
+
+fn path_matches(path: &str, route: &str) -> bool {
+    let route = route.trim_end_matches('/');
+    path.ends_with(route) && (route.starts_with('/') || path[..path.len() - route.len()].ends_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transport::{MemoryTransport, Request, Response, Transport};
+    use reqwest::header::HeaderMap;
+    use reqwest::StatusCode;
+
+    fn request(url: &str) -> Request {
+        Request { url: url.to_string(), headers: HeaderMap::new() }
+    }
+
+    #[tokio::test]
+    async fn test_memory_transport_routes() {
+        let transport = MemoryTransport::new()
+            .with_json("/latest", "all")
+            .with_json("/latest?id=4151", "whip")
+            .with_json("/timeseries?timestep=5m&id=4151", "timeseries");
+
+        let base = "https://prices.runescape.wiki/api/v1/osrs";
+        let body = |response: Response| String::from_utf8(response.body).unwrap();
+        assert_eq!(body(transport.get(request(&format!("{}/latest", base))).await.unwrap()), "all");
+        assert_eq!(body(transport.get(request(&format!("{}/latest?id=4151", base))).await.unwrap()), "whip");
+        assert_eq!(body(transport.get(request(&format!("{}/latest?id=2", base))).await.unwrap()), "all");
+        let url = format!("{}/timeseries?id=4151&timestep=5m", base);
+        assert_eq!(body(transport.get(request(&url)).await.unwrap()), "timeseries");
+        let url = format!("{}/timeseries?id=4151&timestep=1h", base);
+        assert_eq!(transport.get(request(&url)).await.unwrap().status, StatusCode::NOT_FOUND);
+        let url = format!("{}/notlatest", base);
+        assert_eq!(transport.get(request(&url)).await.unwrap().status, StatusCode::NOT_FOUND);
+        assert_eq!(transport.requests().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_memory_transport_sequence() {
+        let transport = MemoryTransport::new()
+            .with_response("/mapping", Response::new(StatusCode::SERVICE_UNAVAILABLE, ""))
+            .with_json("/mapping", "[]");
+        let url = "http://localhost/mapping";
+        assert_eq!(transport.get(request(url)).await.unwrap().status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(transport.get(request(url)).await.unwrap().status, StatusCode::OK);
+        assert_eq!(transport.get(request(url)).await.unwrap().status, StatusCode::OK);
+    }
+}
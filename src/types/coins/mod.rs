@@ -0,0 +1,209 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// An amount of coins, as used for prices and alchemy values.
+///
+/// Serializes as a plain number, so it is wire compatible with the API. `Display` uses the
+/// Grand Exchange shorthand (`950`, `350k`, `1.2m`, `2.1b`), truncating to one decimal; the
+/// alternate form (`{:#}`) prints the full amount with thousands separators.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Coins(u32);
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseCoinsError {
+    #[error("Empty coin amount")]
+    Empty,
+    #[error("Invalid coin amount: {0}")]
+    Invalid(String),
+    #[error("Coin amount out of range: {0}")]
+    Overflow(String),
+}
+
+const SUFFIXES: [(char, u32, u64); 3] = [('k', 3, 1_000), ('m', 6, 1_000_000), ('b', 9, 1_000_000_000)];
+
+impl Coins {
+    pub const ZERO: Coins = Coins(0);
+    pub const MAX: Coins = Coins(u32::MAX);
+
+    pub const fn new(amount: u32) -> Self {
+        Coins(amount)
+    }
+
+    pub const fn amount(&self) -> u32 {
+        self.0
+    }
+
+    pub fn checked_add(self, other: Coins) -> Option<Coins> {
+        self.0.checked_add(other.0).map(Coins)
+    }
+
+    pub fn checked_sub(self, other: Coins) -> Option<Coins> {
+        self.0.checked_sub(other.0).map(Coins)
+    }
+
+    pub fn checked_mul(self, quantity: u32) -> Option<Coins> {
+        self.0.checked_mul(quantity).map(Coins)
+    }
+
+    pub fn saturating_sub(self, other: Coins) -> Coins {
+        Coins(self.0.saturating_sub(other.0))
+    }
+
+    /// Multiplies by a quantity without overflowing, e.g. for the total cost of a buy limit.
+    pub fn widening_mul(self, quantity: u32) -> u64 {
+        u64::from(self.0) * u64::from(quantity)
+    }
+
+    /// Subtracts without underflowing, e.g. for the profit of selling at `self` after buying at `other`.
+    pub fn signed_sub(self, other: Coins) -> i64 {
+        i64::from(self.0) - i64::from(other.0)
+    }
+}
+
+impl From<u32> for Coins {
+    fn from(amount: u32) -> Self {
+        Coins(amount)
+    }
+}
+
+impl From<Coins> for u32 {
+    fn from(coins: Coins) -> Self {
+        coins.0
+    }
+}
+
+impl From<Coins> for u64 {
+    fn from(coins: Coins) -> Self {
+        u64::from(coins.0)
+    }
+}
+
+impl From<Coins> for i64 {
+    fn from(coins: Coins) -> Self {
+        i64::from(coins.0)
+    }
+}
+
+impl Display for Coins {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let amount = u64::from(self.0);
+        if f.alternate() {
+            let digits = amount.to_string();
+            let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+            for (index, digit) in digits.chars().enumerate() {
+                if index > 0 && (digits.len() - index) % 3 == 0 {
+                    grouped.push(',');
+                }
+                grouped.push(digit);
+            }
+            return write!(f, "{}", grouped);
+        }
+        let Some((suffix, _, unit)) = SUFFIXES.iter().rev().find(|(_, _, unit)| amount >= *unit) else {
+            return write!(f, "{}", amount);
+        };
+        let whole = amount / unit;
+        let tenths = amount % unit * 10 / unit;
+        if tenths == 0 {
+            write!(f, "{}{}", whole, suffix)
+        } else {
+            write!(f, "{}.{}{}", whole, tenths, suffix)
+        }
+    }
+}
+
+impl FromStr for Coins {
+    type Err = ParseCoinsError;
+
+    /// Parses plain amounts (`1234`, `1,234`) and shorthand (`350k`, `1.2m`, `2.1B`), with an
+    /// optional `gp` suffix.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseCoinsError::Invalid(value.to_string());
+        let normalized: String = value.trim().to_lowercase().chars().filter(|c| *c != ',' && *c != '_').collect();
+        let normalized = normalized.strip_suffix("gp").unwrap_or(&normalized).trim_end();
+        if normalized.is_empty() {
+            return Err(ParseCoinsError::Empty);
+        }
+        let (number, exponent) = match SUFFIXES.iter().find(|(suffix, _, _)| normalized.ends_with(*suffix)) {
+            Some((_, exponent, _)) => (&normalized[..normalized.len() - 1], *exponent),
+            None => (normalized, 0),
+        };
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+            || fraction.len() > exponent as usize
+        {
+            return Err(invalid());
+        }
+        let digits = format!("{}{:0<width$}", whole, fraction, width = exponent as usize);
+        let amount = digits.parse::<u128>().map_err(|_| ParseCoinsError::Overflow(value.to_string()))?;
+        u32::try_from(amount)
+            .map(Coins)
+            .map_err(|_| ParseCoinsError::Overflow(value.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{Coins, ParseCoinsError};
+
+    #[test]
+    fn test_display_shorthand() {
+        assert_eq!(Coins::new(0).to_string(), "0");
+        assert_eq!(Coins::new(950).to_string(), "950");
+        assert_eq!(Coins::new(1_000).to_string(), "1k");
+        assert_eq!(Coins::new(1_250).to_string(), "1.2k");
+        assert_eq!(Coins::new(350_000).to_string(), "350k");
+        assert_eq!(Coins::new(999_999).to_string(), "999.9k");
+        assert_eq!(Coins::new(1_234_567).to_string(), "1.2m");
+        assert_eq!(Coins::new(2_147_483_647).to_string(), "2.1b");
+        assert_eq!(format!("{:#}", Coins::new(1_234_567)), "1,234,567");
+        assert_eq!(format!("{:#}", Coins::new(999)), "999");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("1234".parse::<Coins>(), Ok(Coins::new(1_234)));
+        assert_eq!("1,234,567".parse::<Coins>(), Ok(Coins::new(1_234_567)));
+        assert_eq!("350k".parse::<Coins>(), Ok(Coins::new(350_000)));
+        assert_eq!("1.2m".parse::<Coins>(), Ok(Coins::new(1_200_000)));
+        assert_eq!(" 2.1B ".parse::<Coins>(), Ok(Coins::new(2_100_000_000)));
+        assert_eq!("1.25k gp".parse::<Coins>(), Ok(Coins::new(1_250)));
+        assert_eq!(".5m".parse::<Coins>(), Ok(Coins::new(500_000)));
+        assert_eq!("".parse::<Coins>(), Err(ParseCoinsError::Empty));
+        assert!(matches!("1.2345k".parse::<Coins>(), Err(ParseCoinsError::Invalid(_))));
+        assert!(matches!("12x".parse::<Coins>(), Err(ParseCoinsError::Invalid(_))));
+        assert!(matches!("-5".parse::<Coins>(), Err(ParseCoinsError::Invalid(_))));
+        assert!(matches!("5b".parse::<Coins>(), Err(ParseCoinsError::Overflow(_))));
+    }
+
+    #[test]
+    fn test_round_trip_shorthand() {
+        for amount in [0, 999, 1_000, 1_500, 350_000, 1_200_000, 2_100_000_000] {
+            let coins = Coins::new(amount);
+            assert_eq!(coins.to_string().parse::<Coins>(), Ok(coins));
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let price = Coins::new(2_000_000_000);
+        assert_eq!(price.checked_add(price), Some(Coins::new(4_000_000_000)));
+        assert_eq!(Coins::MAX.checked_add(Coins::new(1)), None);
+        assert_eq!(price.checked_sub(Coins::new(1)), Some(Coins::new(1_999_999_999)));
+        assert_eq!(Coins::new(1).checked_sub(price), None);
+        assert_eq!(Coins::new(1).saturating_sub(price), Coins::ZERO);
+        assert_eq!(price.checked_mul(3), None);
+        assert_eq!(price.widening_mul(3), 6_000_000_000);
+        assert_eq!(Coins::new(100).signed_sub(Coins::new(250)), -150);
+    }
+
+    #[test]
+    fn test_serde() {
+        let coins: Coins = serde_json::from_str("1500000").unwrap();
+        assert_eq!(coins, Coins::new(1_500_000));
+        assert_eq!(serde_json::to_string(&coins).unwrap(), "1500000");
+        let coins: Option<Coins> = serde_json::from_str("null").unwrap();
+        assert_eq!(coins, None);
+    }
+}
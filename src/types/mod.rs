@@ -1,14 +1,18 @@
 use serde::{de, Deserialize, Deserializer};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+mod coins;
+
+pub use coins::{Coins, ParseCoinsError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
 pub struct ItemId(u32);
 
 impl ItemId {
-    pub fn new(id: u32) -> Self {
+    pub const fn new(id: u32) -> Self {
         ItemId(id)
     }
 
-    pub fn id(&self) -> u32 {
+    pub const fn id(&self) -> u32 {
         self.0
     }
 }
@@ -0,0 +1,386 @@
+use crate::endpoints::latest::{LatestEndpoint, LatestItem};
+use crate::types::{Coins, ItemId};
+use crate::{Client, Error, ReqwestTransport, Transport};
+use chrono::{DateTime, Utc};
+use futures_util::future::{select, Either};
+use futures_util::task::AtomicWaker;
+use futures_util::Stream;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSide {
+    /// The instant-buy price.
+    High,
+    /// The instant-sell price.
+    Low,
+}
+
+#[derive(Debug)]
+pub enum LatestEvent {
+    /// An item that was not listed in earlier polls.
+    Appeared { item_id: ItemId, item: LatestItem },
+    /// A trade newer than the one seen in the previous poll.
+    Trade {
+        item_id: ItemId,
+        side: PriceSide,
+        price: Coins,
+        time: DateTime<Utc>,
+        previous: Option<Coins>,
+    },
+    /// A new trade moved the price by at least the configured percentage.
+    PriceChanged {
+        item_id: ItemId,
+        side: PriceSide,
+        old: Coins,
+        new: Coins,
+        percent: f64,
+    },
+    /// A poll failed. The watcher keeps its state and polls again after the interval.
+    Error(Error),
+}
+
+impl LatestEvent {
+    pub fn item_id(&self) -> Option<ItemId> {
+        match self {
+            LatestEvent::Appeared { item_id, .. }
+            | LatestEvent::Trade { item_id, .. }
+            | LatestEvent::PriceChanged { item_id, .. } => Some(*item_id),
+            LatestEvent::Error(_) => None,
+        }
+    }
+}
+
+/// Stops a [`LatestWatcher`] stream from another task.
+///
+/// After [`WatchHandle::shutdown`] the stream yields the events of the poll it already made and
+/// then ends, interrupting any wait or request in progress.
+#[derive(Debug, Clone, Default)]
+pub struct WatchHandle {
+    shutdown: Arc<Shutdown>,
+}
+
+#[derive(Debug, Default)]
+struct Shutdown {
+    requested: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl WatchHandle {
+    pub fn shutdown(&self) {
+        self.shutdown.requested.store(true, Ordering::Release);
+        self.shutdown.waker.wake();
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown.requested.load(Ordering::Acquire)
+    }
+
+    fn requested(&self) -> ShutdownRequested {
+        ShutdownRequested { shutdown: self.shutdown.clone() }
+    }
+}
+
+struct ShutdownRequested {
+    shutdown: Arc<Shutdown>,
+}
+
+impl Future for ShutdownRequested {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.shutdown.waker.register(cx.waker());
+        if self.shutdown.requested.load(Ordering::Acquire) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Polls [`LatestEndpoint::latest`] at an interval and turns the differences between polls into
+/// a stream of [`LatestEvent`]s.
+///
+/// The first poll only records the current state. Later polls report items that weren't listed
+/// before and trades whose `high_time` or `low_time` moved forward; prices that change without a
+/// newer trade are ignored. Failed polls are reported as [`LatestEvent::Error`] rather than ending
+/// the stream. Waiting between polls uses the client's [`Timer`](crate::Timer).
+///
+/// ```no_run
+/// # async fn example(client: osrs_wiki_prices::Client) {
+/// use futures_util::StreamExt;
+/// use osrs_wiki_prices::watch::{LatestEvent, LatestWatcher};
+/// use std::time::Duration;
+///
+/// let watcher = LatestWatcher::new(client).interval(Duration::from_secs(30)).change_threshold(5.0);
+/// let handle = watcher.handle();
+/// let mut events = std::pin::pin!(watcher.into_stream());
+/// while let Some(event) = events.next().await {
+///     if let LatestEvent::PriceChanged { item_id, percent, .. } = event {
+///         println!("{:?} moved {:.1}%", item_id, percent);
+///     }
+/// }
+/// # }
+/// ```
+pub struct LatestWatcher<T = ReqwestTransport> {
+    client: Client<T>,
+    interval: Duration,
+    change_threshold: Option<f64>,
+    items: Option<HashSet<ItemId>>,
+    handle: WatchHandle,
+}
+
+impl<T: Transport> LatestWatcher<T> {
+    pub fn new(client: Client<T>) -> Self {
+        Self {
+            client,
+            interval: DEFAULT_INTERVAL,
+            change_threshold: None,
+            items: None,
+            handle: WatchHandle::default(),
+        }
+    }
+
+    /// Sets the time to wait between polls. Defaults to 60 seconds.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Emits [`LatestEvent::PriceChanged`] when a new trade differs from the previous price on the
+    /// same side by at least `percent` percent. Disabled by default.
+    pub fn change_threshold(mut self, percent: f64) -> Self {
+        self.change_threshold = Some(percent);
+        self
+    }
+
+    /// Only reports events for the given items.
+    pub fn items(mut self, items: impl IntoIterator<Item=ItemId>) -> Self {
+        self.items = Some(items.into_iter().collect());
+        self
+    }
+
+    /// Returns a handle that stops the stream returned by [`LatestWatcher::into_stream`].
+    pub fn handle(&self) -> WatchHandle {
+        self.handle.clone()
+    }
+
+    /// Starts polling. The first request is sent when the stream is first polled.
+    pub fn into_stream(self) -> impl Stream<Item=LatestEvent> {
+        let state = WatchState { watcher: self, previous: None, pending: VecDeque::new(), polled: false };
+        futures_util::stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((event, state));
+                }
+                let handle = &state.watcher.handle;
+                if handle.is_shutdown() {
+                    return None;
+                }
+                if state.polled {
+                    let sleep = state.watcher.client.timer().sleep(state.watcher.interval);
+                    if let Either::Right(_) = select(sleep, handle.requested()).await {
+                        return None;
+                    }
+                }
+                state.polled = true;
+                let result = match select(pin!(state.watcher.client.latest()), handle.requested()).await {
+                    Either::Left((result, _)) => result,
+                    Either::Right(_) => return None,
+                };
+                state.update(result);
+            }
+        })
+    }
+}
+
+struct WatchState<T> {
+    watcher: LatestWatcher<T>,
+    previous: Option<HashMap<ItemId, LatestItem>>,
+    pending: VecDeque<LatestEvent>,
+    polled: bool,
+}
+
+impl<T> WatchState<T> {
+    fn update(&mut self, result: Result<HashMap<ItemId, LatestItem>, Error>) {
+        let mut current = match result {
+            Ok(current) => current,
+            Err(error) => {
+                self.pending.push_back(LatestEvent::Error(error));
+                return;
+            }
+        };
+        if let Some(items) = &self.watcher.items {
+            current.retain(|item_id, _| items.contains(item_id));
+        }
+        let Some(previous) = &mut self.previous else {
+            self.previous = Some(current);
+            return;
+        };
+        let mut item_ids: Vec<ItemId> = current.keys().copied().collect();
+        item_ids.sort_unstable();
+        for item_id in item_ids {
+            let item = current.remove(&item_id).unwrap();
+            match previous.get(&item_id) {
+                None => self.pending.push_back(LatestEvent::Appeared { item_id, item: item.clone() }),
+                Some(old) => {
+                    let threshold = self.watcher.change_threshold;
+                    let high = ((old.high, old.high_time), (item.high, item.high_time));
+                    let low = ((old.low, old.low_time), (item.low, item.low_time));
+                    for (side, (old, new)) in [(PriceSide::High, high), (PriceSide::Low, low)] {
+                        self.pending.extend(side_events(item_id, side, old, new, threshold));
+                    }
+                }
+            }
+            // Items missing from a poll keep their last known state, so they don't reappear.
+            previous.insert(item_id, item);
+        }
+    }
+}
+
+type Side = (Option<Coins>, Option<DateTime<Utc>>);
+
+fn side_events(item_id: ItemId, side: PriceSide, old: Side, new: Side, threshold: Option<f64>) -> Vec<LatestEvent> {
+    let ((old_price, old_time), (Some(price), Some(time))) = (old, new) else {
+        return Vec::new();
+    };
+    if old_time.is_some_and(|old_time| time <= old_time) {
+        return Vec::new();
+    }
+    let mut events = vec![LatestEvent::Trade { item_id, side, price, time, previous: old_price }];
+    if let (Some(threshold), Some(old)) = (threshold, old_price)
+        && old > Coins::ZERO
+    {
+        let percent = price.signed_sub(old) as f64 / f64::from(old.amount()) * 100.0;
+        if percent.abs() >= threshold {
+            events.push(LatestEvent::PriceChanged { item_id, side, old, new: price, percent });
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::RecordingTimer;
+    use crate::transport::{MemoryTransport, Response};
+    use crate::types::{Coins, ItemId};
+    use crate::watch::{LatestEvent, LatestWatcher, PriceSide};
+    use crate::{Client, Error, RetryPolicy};
+    use futures_util::StreamExt;
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    const FIRST: &str = r#"{"data":{
+        "4151":{"high":1500000,"highTime":1760702000,"low":1490000,"lowTime":1760702000},
+        "554":{"high":5,"highTime":1760702000,"low":4,"lowTime":1760702000}
+    }}"#;
+    const SECOND: &str = r#"{"data":{
+        "4151":{"high":1650000,"highTime":1760702060,"low":1495000,"lowTime":1760702060},
+        "554":{"high":6,"highTime":1760702000,"low":4,"lowTime":1760702000},
+        "561":{"high":105,"highTime":1760702050,"low":103,"lowTime":1760702040}
+    }}"#;
+
+    fn client(transport: MemoryTransport, timer: RecordingTimer) -> Client<MemoryTransport> {
+        Client::builder()
+            .user_agent("test")
+            .retry_policy(RetryPolicy::none())
+            .no_rate_limit()
+            .timer(timer)
+            .build_with_transport(transport)
+    }
+
+    #[tokio::test]
+    async fn test_events() {
+        let transport = MemoryTransport::new();
+        transport.push_response("/latest", Response::json(FIRST));
+        transport.push_response("/latest", Response::json(SECOND));
+        transport.push_response("/latest", Response::new(StatusCode::SERVICE_UNAVAILABLE, "unavailable"));
+        transport.push_response("/latest", Response::json(SECOND));
+        let timer = RecordingTimer::default();
+        let watcher = LatestWatcher::new(client(transport.clone(), timer.clone()))
+            .interval(Duration::from_secs(30))
+            .change_threshold(5.0);
+        let handle = watcher.handle();
+        let mut events = std::pin::pin!(watcher.into_stream());
+
+        // Events are ordered by item id, and the fire rune (554) price changed without a newer
+        // trade so it is not reported.
+        match events.next().await.unwrap() {
+            LatestEvent::Appeared { item_id, item } => {
+                assert_eq!(item_id, ItemId::new(561));
+                assert_eq!(item.high, Some(Coins::new(105)));
+            }
+            event => panic!("Unexpected event {:?}", event),
+        }
+        match events.next().await.unwrap() {
+            LatestEvent::Trade { item_id, side, price, previous, .. } => {
+                assert_eq!(item_id, ItemId::new(4151));
+                assert_eq!(side, PriceSide::High);
+                assert_eq!(price, Coins::new(1650000));
+                assert_eq!(previous, Some(Coins::new(1500000)));
+            }
+            event => panic!("Unexpected event {:?}", event),
+        }
+        match events.next().await.unwrap() {
+            LatestEvent::PriceChanged { item_id, side, percent, .. } => {
+                assert_eq!(item_id, ItemId::new(4151));
+                assert_eq!(side, PriceSide::High);
+                assert!((percent - 10.0).abs() < 1e-9);
+            }
+            event => panic!("Unexpected event {:?}", event),
+        }
+        // A smaller move is reported as a trade only.
+        match events.next().await.unwrap() {
+            LatestEvent::Trade { item_id, side, .. } => assert_eq!((item_id, side), (ItemId::new(4151), PriceSide::Low)),
+            event => panic!("Unexpected event {:?}", event),
+        }
+        let event = events.next().await.unwrap();
+        assert!(matches!(event, LatestEvent::Error(Error::Status { status: StatusCode::SERVICE_UNAVAILABLE, .. })));
+        assert_eq!(event.item_id(), None);
+
+        handle.shutdown();
+        assert!(events.next().await.is_none());
+        assert_eq!(transport.requests().len(), 3);
+        assert_eq!(*timer.sleeps.lock().unwrap(), vec![Duration::from_secs(30); 2]);
+    }
+
+    #[tokio::test]
+    async fn test_item_filter() {
+        let transport = MemoryTransport::new();
+        transport.push_response("/latest", Response::json(FIRST));
+        transport.push_response("/latest", Response::json(SECOND));
+        let watcher = LatestWatcher::new(client(transport, RecordingTimer::default())).items([ItemId::new(561)]);
+        let handle = watcher.handle();
+        let mut events = std::pin::pin!(watcher.into_stream());
+
+        let event = events.next().await.unwrap();
+        assert!(matches!(event, LatestEvent::Appeared { .. }));
+        assert_eq!(event.item_id(), Some(ItemId::new(561)));
+        handle.shutdown();
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_interrupts_wait() {
+        let transport = MemoryTransport::new().with_json("/latest", FIRST);
+        let client = Client::builder()
+            .user_agent("test")
+            .no_rate_limit()
+            .build_with_transport(transport);
+        let watcher = LatestWatcher::new(client).interval(Duration::from_secs(3600));
+        let handle = watcher.handle();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            handle.shutdown();
+        });
+        let events = tokio::time::timeout(Duration::from_secs(5), watcher.into_stream().collect::<Vec<_>>()).await;
+        assert!(events.unwrap().is_empty());
+    }
+}